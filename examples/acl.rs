@@ -1,9 +1,22 @@
+use lazy_static::lazy_static;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use valkey_module::alloc::ValkeyAlloc;
 use valkey_module::{
-    valkey_module, AclPermissions, Context, NextArg, ValkeyError, ValkeyResult, ValkeyString,
-    ValkeyValue, VALKEY_OK,
+    valkey_module, AclChannelFlags, AclLogEntryReason, AclPermissions, AclRules, Context,
+    ModuleUser, NextArg, ValkeyError, ValkeyGILGuard, ValkeyResult, ValkeyString, ValkeyValue,
+    VALKEY_OK,
 };
 
+lazy_static! {
+    static ref SANDBOX_USER: ValkeyGILGuard<Option<ModuleUser>> = ValkeyGILGuard::default();
+}
+
+static SANDBOX_LOGOUTS: AtomicUsize = AtomicUsize::new(0);
+
+fn sandbox_rules() -> AclRules {
+    AclRules::new().on().allow_command("get").keys("sandbox:*")
+}
+
 fn verify_key_access_for_user(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     let mut args = args.into_iter().skip(1);
     let user = args.next_arg()?;
@@ -19,6 +32,84 @@ fn get_current_user(ctx: &Context, _args: Vec<ValkeyString>) -> ValkeyResult {
     Ok(ValkeyValue::BulkValkeyString(ctx.get_current_user()))
 }
 
+fn exec_as(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    if args.len() < 3 {
+        return Err(ValkeyError::WrongArity);
+    }
+    let user = &args[1];
+    let command = &args[2..];
+    if ctx.acl_check_command_permission(user, command).is_err() {
+        let _ = ctx.acl_add_log_entry_by_user_name(user, &command[0], AclLogEntryReason::Command);
+        return Err(ValkeyError::Str(
+            "NOPERM this user has no permissions to run the command",
+        ));
+    }
+    ctx.cmd(command[0].try_as_str()?)
+        .args(&command[1..])
+        .query()
+}
+
+fn publish_as(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let user = args.next_arg()?;
+    let channel = args.next_arg()?;
+    let message = args.next_arg()?;
+    if ctx
+        .acl_check_channel_permission(&user, &channel, &AclChannelFlags::PUBLISH)
+        .is_err()
+    {
+        let _ = ctx.acl_add_log_entry_by_user_name(&user, &channel, AclLogEntryReason::Channel);
+        return Err(ValkeyError::Str(
+            "NOPERM this user has no permissions to access the channel",
+        ));
+    }
+    ctx.cmd("PUBLISH").arg(&channel).arg(&message).query()
+}
+
+fn sandboxed_get(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let key = args.next_arg()?;
+
+    let user = ModuleUser::new("sandbox")?;
+    user.set_rules(&sandbox_rules())?;
+    ctx.with_user(&user, |ctx| ctx.call("GET", &[&key]))
+}
+
+/// Reads the key as a reader user, nested in the sandbox, then again once
+/// the sandbox user is restored.
+fn nested_sandboxed_get(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let key = args.next_arg()?;
+
+    let user = ModuleUser::new("sandbox")?;
+    user.set_rules(&sandbox_rules())?;
+    let reader = ModuleUser::new("reader")?;
+    reader.set_rules(&AclRules::new().on().allow_command("get").all_keys())?;
+    ctx.with_user(&user, |user_ctx| {
+        user_ctx.with_user(&reader, |reader_ctx| reader_ctx.call("GET", &[&key]))?;
+        user_ctx.call("GET", &[&key])
+    })
+}
+
+fn sandbox_login(ctx: &Context, _args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut user = SANDBOX_USER.lock(ctx);
+    if user.is_none() {
+        let new_user = ModuleUser::new("sandbox")?;
+        new_user.set_rules(&sandbox_rules())?;
+        *user = Some(new_user);
+    }
+    let client_id = ctx.authenticate_client_with_user(user.as_ref().unwrap(), |_client_id| {
+        SANDBOX_LOGOUTS.fetch_add(1, Ordering::SeqCst);
+    })?;
+    Ok(ValkeyValue::Integer(client_id as i64))
+}
+
+fn sandbox_logouts(_ctx: &Context, _args: Vec<ValkeyString>) -> ValkeyResult {
+    Ok(ValkeyValue::Integer(
+        SANDBOX_LOGOUTS.load(Ordering::SeqCst) as i64
+    ))
+}
+
 fn custom_category(_ctx: &Context, _args: Vec<ValkeyString>) -> ValkeyResult {
     VALKEY_OK
 }
@@ -43,6 +134,12 @@ valkey_module! {
     commands: [
         ["verify_key_access_for_user", verify_key_access_for_user, "", 0, 0, 0],
         ["get_current_user", get_current_user, "", 0, 0, 0],
+        ["acl.exec_as", exec_as, "", 0, 0, 0],
+        ["acl.publish_as", publish_as, "", 0, 0, 0],
+        ["acl.sandboxed_get", sandboxed_get, "", 0, 0, 0],
+        ["acl.nested_sandboxed_get", nested_sandboxed_get, "", 0, 0, 0],
+        ["acl.sandbox_login", sandbox_login, "", 0, 0, 0],
+        ["acl.sandbox_logouts", sandbox_logouts, "", 0, 0, 0],
         ["custom_category", custom_category, "write",  0, 0, 0, "custom_acl_one"],
         ["custom_categories", custom_categories, "", 0, 0, 0, "custom_acl_one custom_acl_two"],
         ["existing_categories", existing_categories, "write", 0, 0, 0, "read fast admin"],
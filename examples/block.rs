@@ -1,8 +1,10 @@
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::thread;
 use std::time::Duration;
 use valkey_module::alloc::ValkeyAlloc;
 use valkey_module::{
-    valkey_module, Context, ThreadSafeContext, ValkeyResult, ValkeyString, ValkeyValue,
+    valkey_module, BlockOnKeysFlags, Context, NextArg, ThreadSafeContext, ValkeyError,
+    ValkeyResult, ValkeyString, ValkeyValue,
 };
 
 fn block(ctx: &Context, _args: Vec<ValkeyString>) -> ValkeyResult {
@@ -18,6 +20,146 @@ fn block(ctx: &Context, _args: Vec<ValkeyString>) -> ValkeyResult {
     Ok(ValkeyValue::NoReply)
 }
 
+/// block.sum <n> <delay_ms> <timeout_ms>
+///
+/// Sums the integers up to `n` on a worker thread, which takes `delay_ms`, and
+/// replies with the sum from the main thread. The time spent on the worker
+/// thread is accounted to the command.
+fn block_sum(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let n = args.next_u64()?;
+    let delay = args.next_u64()?;
+    let timeout = args.next_u64()?;
+    args.done()?;
+
+    let blocked_client = ctx.block_client_with(
+        Duration::from_millis(timeout),
+        |_ctx, sum: u64| Ok(ValkeyValue::Integer(sum as i64)),
+        |_ctx| Err(ValkeyError::Str("ERR timed out")),
+        None,
+    );
+
+    thread::spawn(move || {
+        // Accounts the time spent here to the command, in SLOWLOG for instance
+        let measure = blocked_client.measure_time();
+        thread::sleep(Duration::from_millis(delay));
+        let sum = (1..=n).sum();
+        drop(measure);
+        blocked_client.unblock(sum);
+    });
+
+    Ok(ValkeyValue::NoReply)
+}
+
+static DISCONNECTED_SCANS: AtomicI64 = AtomicI64::new(0);
+static CANCELLED_SCANS: AtomicI64 = AtomicI64::new(0);
+
+/// block.scan <steps>
+///
+/// Runs `steps` steps of 10ms on a worker thread, which stops early if the
+/// client disconnects.
+fn block_scan(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let steps = args.next_u64()?;
+    args.done()?;
+
+    let mut blocked_client = ctx.block_client();
+    blocked_client.on_disconnect(ctx, |_ctx| {
+        DISCONNECTED_SCANS.fetch_add(1, Ordering::Relaxed);
+    });
+    let token = blocked_client.cancellation_token(ctx);
+
+    thread::spawn(move || {
+        let thread_ctx = ThreadSafeContext::with_blocked_client(blocked_client);
+        for _ in 0..steps {
+            if token.is_cancelled() {
+                CANCELLED_SCANS.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        thread_ctx.reply(Ok(ValkeyValue::Integer(steps as i64)));
+    });
+
+    Ok(ValkeyValue::NoReply)
+}
+
+/// block.scan_stats
+///
+/// Replies with the number of disconnected and cancelled scans.
+fn block_scan_stats(_ctx: &Context, _args: Vec<ValkeyString>) -> ValkeyResult {
+    Ok(ValkeyValue::Array(vec![
+        ValkeyValue::Integer(DISCONNECTED_SCANS.load(Ordering::Relaxed)),
+        ValkeyValue::Integer(CANCELLED_SCANS.load(Ordering::Relaxed)),
+    ]))
+}
+
+/// block.lpop <key> [<key> ...] <timeout_ms>
+///
+/// Like `BLPOP`, pops the first element of the first non empty list, or blocks
+/// until one of the lists is pushed to.
+fn block_lpop(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    if args.len() < 3 {
+        return Err(ValkeyError::WrongArity);
+    }
+    let mut keys: Vec<ValkeyString> = args.into_iter().skip(1).collect();
+    let timeout = keys.pop().unwrap().parse_unsigned_integer()?;
+
+    for key in &keys {
+        if let Some(reply) = lpop(ctx, key) {
+            return reply;
+        }
+    }
+
+    ctx.block_client_on_keys(
+        &keys,
+        Duration::from_millis(timeout),
+        |ctx, key, _| lpop(ctx, &key),
+        |_, _| Ok(ValkeyValue::Null),
+        (),
+    );
+    Ok(ValkeyValue::NoReply)
+}
+
+fn lpop(ctx: &Context, key: &ValkeyString) -> Option<ValkeyResult> {
+    match ctx.call("LPOP", &[key]) {
+        Ok(ValkeyValue::Null) => None,
+        Ok(value) => Some(Ok(ValkeyValue::Array(vec![
+            ValkeyValue::BulkValkeyString(key.safe_clone(ctx)),
+            value,
+        ]))),
+        Err(e) => Some(Err(e)),
+    }
+}
+
+/// block.wait_del <key> <timeout_ms>
+///
+/// Blocks until the key is deleted, replying with the number of times the key
+/// was written to meanwhile.
+fn block_wait_del(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let key = args.next_arg()?;
+    let timeout = args.next_u64()?;
+    args.done()?;
+
+    ctx.block_client_on_keys_with_flags(
+        &[key],
+        Duration::from_millis(timeout),
+        |ctx, key, writes: &mut i64| match ctx.call("EXISTS", &[&key]) {
+            Ok(ValkeyValue::Integer(0)) => Some(Ok(ValkeyValue::Integer(*writes))),
+            Ok(_) => {
+                *writes += 1;
+                None
+            }
+            Err(e) => Some(Err(e)),
+        },
+        |_, _| Ok(ValkeyValue::Null),
+        0,
+        BlockOnKeysFlags::UNBLOCK_DELETED,
+    );
+    Ok(ValkeyValue::NoReply)
+}
+
 //////////////////////////////////////////////////////
 
 valkey_module! {
@@ -27,5 +169,10 @@ valkey_module! {
     data_types: [],
     commands: [
         ["block", block, "", 0, 0, 0],
+        ["block.sum", block_sum, "", 0, 0, 0],
+        ["block.scan", block_scan, "", 0, 0, 0],
+        ["block.scan_stats", block_scan_stats, "", 0, 0, 0],
+        ["block.lpop", block_lpop, "write", 1, -2, 1],
+        ["block.wait_del", block_wait_del, "readonly", 1, 1, 1],
     ],
 }
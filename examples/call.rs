@@ -1,7 +1,7 @@
 use valkey_module::{
     valkey_module, BlockedClient, CallOptionResp, CallOptionsBuilder, CallReply, CallResult,
-    Context, FutureCallReply, PromiseCallReply, ThreadSafeContext, ValkeyError, ValkeyResult,
-    ValkeyString, ValkeyValue,
+    Context, FutureCallReply, NextArg, PromiseCallReply, ThreadSafeContext, ValkeyError,
+    ValkeyResult, ValkeyString, ValkeyValue,
 };
 
 use std::thread;
@@ -161,6 +161,70 @@ fn call_blocking_from_detach_ctx(ctx: &Context, _: Vec<ValkeyString>) -> ValkeyR
     Ok(ValkeyValue::NoReply)
 }
 
+fn call_forward(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let command = args.next_string()?;
+    let args: Vec<ValkeyString> = args.collect();
+    let args: Vec<&ValkeyString> = args.iter().collect();
+
+    let call_options = CallOptionsBuilder::new()
+        .resp(CallOptionResp::Auto)
+        .errors_as_replies()
+        .build();
+    let res: CallResult = ctx.call_ext(&command, &call_options, args.as_slice());
+    ctx.reply_with_call_result(&res);
+    Ok(ValkeyValue::NoReply)
+}
+
+fn call_proto(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let command = args.next_string()?;
+    let args: Vec<ValkeyString> = args.collect();
+    let args: Vec<&ValkeyString> = args.iter().collect();
+
+    let call_options = CallOptionsBuilder::new().build();
+    let res: CallResult = ctx.call_ext(&command, &call_options, args.as_slice());
+    let reply = res.map_err(|e| -> ValkeyError { e.into() })?;
+    Ok(ValkeyValue::StringBuffer(reply.raw_resp_bytes().to_vec()))
+}
+
+fn call_typed(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let key = args.next_string()?;
+    let zrange_args = [key.as_str(), "0", "-1", "WITHSCORES"];
+
+    // RESP2 replies with a flat array, RESP3 with an array of pairs.
+    let resp2: Vec<(String, f64)> = ctx.call_typed("ZRANGE", &zrange_args)?;
+    let call_options = CallOptionsBuilder::new()
+        .resp(CallOptionResp::Resp3)
+        .build();
+    let res: CallResult = ctx.call_ext("ZRANGE", &call_options, &zrange_args);
+    let resp3: Vec<(String, f64)> = valkey_module::from_call_result(&res)?;
+
+    if resp2 != resp3 {
+        return Err(ValkeyError::Str("RESP2 and RESP3 replies differ"));
+    }
+    Ok(resp2
+        .into_iter()
+        .map(|(member, score)| ValkeyValue::Array(vec![member.into(), score.into()]))
+        .collect::<Vec<_>>()
+        .into())
+}
+
+fn call_builder(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let key = args.next_arg()?;
+    let score = args.next_f64()?;
+    let member = args.next_arg()?;
+
+    ctx.cmd("ZADD").arg(&key).arg(score).arg(&member).exec()?;
+    ctx.cmd("ZSCORE")
+        .arg(&key)
+        .arg(&member)
+        .options(CallOptionsBuilder::new().resp(CallOptionResp::Resp3))
+        .query()
+}
+
 //////////////////////////////////////////////////////
 
 valkey_module! {
@@ -172,6 +236,10 @@ valkey_module! {
         ["call.test", call_test, "", 0, 0, 0],
         ["call.blocking", call_blocking, "", 0, 0, 0],
         ["call.blocking_from_detached_ctx", call_blocking_from_detach_ctx, "", 0, 0, 0],
+        ["call.forward", call_forward, "", 0, 0, 0],
+        ["call.proto", call_proto, "", 0, 0, 0],
+        ["call.typed", call_typed, "", 0, 0, 0],
+        ["call.builder", call_builder, "", 0, 0, 0],
     ],
 }
 
@@ -0,0 +1,84 @@
+use lazy_static::lazy_static;
+use valkey_module::alloc::ValkeyAlloc;
+use valkey_module::{
+    valkey_module, Context, ModuleDict, NextArg, ValkeyGILGuard, ValkeyResult, ValkeyString,
+    ValkeyValue,
+};
+
+lazy_static! {
+    static ref DICT: ValkeyGILGuard<ModuleDict<Vec<u8>>> = ValkeyGILGuard::default();
+}
+
+fn entries<'a>(iter: impl Iterator<Item = (Vec<u8>, &'a Vec<u8>)>) -> ValkeyValue {
+    ValkeyValue::Array(
+        iter.flat_map(|(key, value)| {
+            [
+                ValkeyValue::StringBuffer(key),
+                ValkeyValue::StringBuffer(value.clone()),
+            ]
+        })
+        .collect(),
+    )
+}
+
+fn dict_set(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let key = args.next_arg()?;
+    let value = args.next_arg()?;
+    let old = DICT.lock(ctx).insert(key, value.as_slice().to_vec());
+    Ok(old.into())
+}
+
+fn dict_get(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let key = args.next_arg()?;
+    Ok(DICT.lock(ctx).get(&key).cloned().into())
+}
+
+fn dict_del(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let key = args.next_arg()?;
+    Ok(DICT.lock(ctx).remove(&key).into())
+}
+
+fn dict_range(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let start = args.next_arg()?;
+    let end = args.next_arg()?;
+    let dict = DICT.lock(ctx);
+    Ok(entries(dict.range(&start..&end)))
+}
+
+fn dict_seek_rev(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let op = args.next_str()?;
+    let key = args.next_arg()?;
+    let dict = DICT.lock(ctx);
+    let mut iter = dict.seek(op, &key)?;
+    Ok(entries(std::iter::from_fn(|| iter.prev())))
+}
+
+fn dict_info(ctx: &Context, _args: Vec<ValkeyString>) -> ValkeyResult {
+    let dict = DICT.lock(ctx);
+    Ok(ValkeyValue::Array(vec![
+        ValkeyValue::Integer(dict.len() as i64),
+        ValkeyValue::Integer(dict.malloc_size() as i64),
+    ]))
+}
+
+//////////////////////////////////////////////////////
+
+valkey_module! {
+    name: "dict",
+    version: 1,
+    allocator: (ValkeyAlloc, ValkeyAlloc),
+    data_types: [],
+    commands: [
+        ["dict.set", dict_set, "", 0, 0, 0],
+        ["dict.get", dict_get, "", 0, 0, 0],
+        ["dict.del", dict_del, "", 0, 0, 0],
+        ["dict.range", dict_range, "", 0, 0, 0],
+        ["dict.seek_rev", dict_seek_rev, "", 0, 0, 0],
+        ["dict.info", dict_info, "", 0, 0, 0],
+    ],
+}
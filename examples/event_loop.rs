@@ -0,0 +1,115 @@
+use lazy_static::lazy_static;
+use std::io::{ErrorKind, Read, Write};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::UnixStream;
+use std::thread;
+use valkey_module::alloc::ValkeyAlloc;
+use valkey_module::{
+    valkey_module, Context, EventLoopHandle, EventLoopMask, NextArg, Status, ValkeyError,
+    ValkeyGILGuard, ValkeyResult, ValkeyString, ValkeyValue,
+};
+
+/// Messages are sent by background threads to one end of the socket pair, and
+/// read on the main thread from the other end, registered in the event loop.
+struct Bridge {
+    sender: UnixStream,
+    handle: EventLoopHandle,
+}
+
+lazy_static! {
+    static ref BRIDGE: ValkeyGILGuard<Option<Bridge>> = ValkeyGILGuard::default();
+}
+
+fn init(ctx: &Context, _args: &[ValkeyString]) -> Status {
+    let Ok((sender, mut receiver)) = UnixStream::pair() else {
+        return Status::Err;
+    };
+    if receiver.set_nonblocking(true).is_err() {
+        return Status::Err;
+    }
+
+    let mut pending = Vec::new();
+    let handle = ctx.event_loop_register(
+        receiver.as_raw_fd(),
+        EventLoopMask::READABLE,
+        move |ctx, _mask| {
+            let mut buf = [0; 1024];
+            loop {
+                match receiver.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => pending.extend_from_slice(&buf[..n]),
+                    Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(_) => break,
+                }
+            }
+            // Each message is a line, the last one may still be incomplete
+            while let Some(end) = pending.iter().position(|b| *b == b'\n') {
+                let message: Vec<u8> = pending.drain(..=end).collect();
+                let _ = ctx.call("RPUSH", &[b"messages".as_slice(), &message[..end]]);
+            }
+        },
+    );
+    match handle {
+        Ok(handle) => {
+            *BRIDGE.lock(ctx) = Some(Bridge { sender, handle });
+            Status::Ok
+        }
+        Err(_) => Status::Err,
+    }
+}
+
+fn deinit(ctx: &Context) -> Status {
+    BRIDGE.lock(ctx).take();
+    Status::Ok
+}
+
+/// Sends the message from a background thread, the main thread pushes it to
+/// the `messages` list once it is received.
+fn send(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let mut message = args.next_arg()?.as_slice().to_vec();
+    args.done()?;
+    if message.contains(&b'\n') {
+        return Err(ValkeyError::Str(
+            "ERR the message can not contain a new line",
+        ));
+    }
+    message.push(b'\n');
+
+    let bridge = BRIDGE.lock(ctx);
+    let mut sender = bridge
+        .as_ref()
+        .ok_or(ValkeyError::Str("ERR the bridge is not started"))?
+        .sender
+        .try_clone()?;
+    thread::spawn(move || sender.write_all(&message));
+
+    Ok(ValkeyValue::SimpleStringStatic("OK"))
+}
+
+/// Registers the receiving end again, which fails while it is registered.
+fn register_again(ctx: &Context, _args: Vec<ValkeyString>) -> ValkeyResult {
+    let bridge = BRIDGE.lock(ctx);
+    let fd = bridge
+        .as_ref()
+        .ok_or(ValkeyError::Str("ERR the bridge is not started"))?
+        .handle
+        .fd();
+    ctx.event_loop_register(fd, EventLoopMask::WRITABLE, |_ctx, _mask| {})?;
+    Ok(ValkeyValue::SimpleStringStatic("OK"))
+}
+
+//////////////////////////////////////////////////////
+
+valkey_module! {
+    name: "event_loop",
+    version: 1,
+    allocator: (ValkeyAlloc, ValkeyAlloc),
+    data_types: [],
+    init: init,
+    deinit: deinit,
+    commands: [
+        ["event_loop.send", send, "", 0, 0, 0],
+        ["event_loop.register_again", register_again, "", 0, 0, 0],
+    ],
+}
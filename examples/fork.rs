@@ -0,0 +1,79 @@
+use lazy_static::lazy_static;
+use std::thread;
+use std::time::Duration;
+use valkey_module::alloc::ValkeyAlloc;
+use valkey_module::{
+    valkey_module, Context, ForkExitStatus, ForkHandle, NextArg, ValkeyError, ValkeyGILGuard,
+    ValkeyResult, ValkeyString, ValkeyValue,
+};
+
+#[derive(Default)]
+struct ForkState {
+    handle: Option<ForkHandle>,
+    status: Option<String>,
+}
+
+lazy_static! {
+    static ref FORK_STATE: ValkeyGILGuard<ForkState> = ValkeyGILGuard::default();
+}
+
+fn fork_start(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let duration_ms = args.next_u64()?;
+    let fail = args.next_bool()?;
+
+    let handle = ctx.fork(
+        |child| {
+            for i in 0..10 {
+                thread::sleep(Duration::from_millis(duration_ms / 10));
+                child.heartbeat(f64::from(i + 1) / 10.0);
+            }
+            if fail {
+                return Err(ValkeyError::Str("snapshot failed"));
+            }
+            Ok(())
+        },
+        |ctx, status| {
+            let status = match status {
+                ForkExitStatus::Exited(code) => format!("exited:{code}"),
+                ForkExitStatus::Signaled(signal) => format!("signaled:{signal}"),
+                ForkExitStatus::Killed => "killed".to_owned(),
+            };
+            FORK_STATE.lock(ctx).status = Some(status);
+        },
+    )?;
+
+    let mut state = FORK_STATE.lock(ctx);
+    state.status = None;
+    state.handle = Some(handle);
+    Ok(ValkeyValue::SimpleStringStatic("OK"))
+}
+
+fn fork_kill(ctx: &Context, _args: Vec<ValkeyString>) -> ValkeyResult {
+    let handle = FORK_STATE.lock(ctx).handle.take();
+    match handle {
+        Some(handle) => {
+            handle.kill(ctx)?;
+            Ok(ValkeyValue::SimpleStringStatic("OK"))
+        }
+        None => Err(ValkeyError::Str("ERR no fork child was started")),
+    }
+}
+
+fn fork_status(ctx: &Context, _args: Vec<ValkeyString>) -> ValkeyResult {
+    Ok(FORK_STATE.lock(ctx).status.clone().into())
+}
+
+//////////////////////////////////////////////////////
+
+valkey_module! {
+    name: "fork",
+    version: 1,
+    allocator: (ValkeyAlloc, ValkeyAlloc),
+    data_types: [],
+    commands: [
+        ["fork.start", fork_start, "", 0, 0, 0],
+        ["fork.kill", fork_kill, "", 0, 0, 0],
+        ["fork.status", fork_status, "", 0, 0, 0],
+    ],
+}
@@ -1,16 +1,20 @@
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::time::Duration;
 
 use valkey_module::alloc::ValkeyAlloc;
-use valkey_module::ValkeyError;
-use valkey_module::{valkey_module, Context, ValkeyResult, ValkeyString, ValkeyValue};
-use valkey_module_macros::{command, ValkeyValue};
+use valkey_module::{executor, ValkeyError};
+use valkey_module::{
+    valkey_module, BlockedClient, Context, ThreadSafeContext, ValkeyResult, ValkeyString,
+    ValkeyValue,
+};
+use valkey_module_macros::{command, FromValkeyValue, ValkeyArgs, ValkeyValue};
 
-#[derive(ValkeyValue)]
+#[derive(ValkeyValue, FromValkeyValue)]
 struct ValkeyValueDeriveInner {
     i1: i64,
 }
 
-#[derive(ValkeyValue)]
+#[derive(ValkeyValue, FromValkeyValue)]
 struct ValkeyValueDerive {
     i: i64,
     f: f64,
@@ -26,7 +30,7 @@ struct ValkeyValueDerive {
     ordered_set: BTreeSet<String>,
 }
 
-#[derive(ValkeyValue)]
+#[derive(ValkeyValue, FromValkeyValue)]
 enum ValkeyValueEnum {
     Str(String),
     ValkeyValue(ValkeyValueDerive),
@@ -128,6 +132,130 @@ fn num_keys(_ctx: &Context, _args: Vec<ValkeyString>) -> ValkeyResult {
     Ok(ValkeyValue::SimpleStringStatic("OK"))
 }
 
+#[command(
+    {
+        flags: [Fast],
+        arity: 3,
+        key_spec: [],
+        redact: [2]
+    }
+)]
+fn redacted_password(_ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let valid = args[2].as_slice() == b"secret";
+    Ok(ValkeyValue::Bool(valid))
+}
+
+#[derive(ValkeyArgs)]
+enum Aggregate {
+    Sum,
+    Min,
+    Max,
+}
+
+#[derive(ValkeyArgs)]
+struct ValkeyArgsDerive {
+    key: ValkeyString,
+    #[ValkeyArgsAttr{flag: "NX"}]
+    nx: bool,
+    #[ValkeyArgsAttr{keyword: "EX"}]
+    ex: Option<u64>,
+    #[ValkeyArgsAttr{keyword: "AGGREGATE"}]
+    aggregate: Option<Aggregate>,
+    #[ValkeyArgsAttr{variadic: true}]
+    members: Vec<String>,
+}
+
+#[command(
+    {
+        flags: [ReadOnly],
+        arity: -2,
+        key_spec: [
+            {
+                notes: "test valkey args derive macro",
+                flags: [ReadOnly, Access],
+                begin_search: Index({ index : 1 }),
+                find_keys: Range({ last_key : 0, steps : 1, limit : 0 }),
+            }
+        ]
+    }
+)]
+fn valkey_args_derive(_ctx: &Context, args: ValkeyArgsDerive) -> ValkeyResult {
+    let aggregate = match args.aggregate {
+        Some(Aggregate::Sum) => "sum",
+        Some(Aggregate::Min) => "min",
+        Some(Aggregate::Max) => "max",
+        None => "none",
+    };
+    Ok(ValkeyValue::Array(vec![
+        args.key.into(),
+        ValkeyValue::Bool(args.nx),
+        args.ex
+            .map_or(ValkeyValue::Null, |ex| ValkeyValue::Integer(ex as i64)),
+        ValkeyValue::SimpleStringStatic(aggregate),
+        args.members.into(),
+    ]))
+}
+
+async fn strlen(ctx: &ThreadSafeContext<BlockedClient>, key: &[u8]) -> ValkeyResult {
+    // The GIL is only held while the command runs, not across awaits
+    ctx.lock_async().await.call("STRLEN", &[key])
+}
+
+#[command(
+    {
+        flags: [ReadOnly],
+        arity: -2,
+        key_spec: [
+            {
+                notes: "test async command, all the arguments are keys",
+                flags: [ReadOnly, Access],
+                begin_search: Index({ index : 1 }),
+                find_keys: Range({ last_key :- 1, steps : 1, limit : 0 }),
+            }
+        ]
+    }
+)]
+async fn async_strlen_sum(
+    ctx: &ThreadSafeContext<BlockedClient>,
+    args: Vec<Vec<u8>>,
+) -> ValkeyResult {
+    let mut sum = 0;
+    for key in &args[1..] {
+        if let ValkeyValue::Integer(len) = strlen(ctx, key).await? {
+            sum += len;
+        }
+    }
+    Ok(ValkeyValue::Integer(sum))
+}
+
+#[command(
+    {
+        flags: [Write],
+        arity: 2,
+        key_spec: []
+    }
+)]
+async fn async_sleep(ctx: &ThreadSafeContext<BlockedClient>, args: Vec<Vec<u8>>) -> ValkeyResult {
+    let millis = std::str::from_utf8(&args[1])
+        .ok()
+        .and_then(|millis| millis.parse().ok())
+        .ok_or(ValkeyError::Str("ERR invalid number of milliseconds"))?;
+    // The other async commands keep running while this one waits
+    executor::sleep(Duration::from_millis(millis)).await;
+    ctx.lock_async().await.call("INCR", &["async_sleeps"])
+}
+
+#[command(
+    {
+        flags: [ReadOnly],
+        arity: 1,
+        key_spec: []
+    }
+)]
+async fn async_panic(_ctx: &ThreadSafeContext<BlockedClient>, _args: Vec<Vec<u8>>) -> ValkeyResult {
+    panic!("the async command failed");
+}
+
 valkey_module! {
     name: "server_events",
     version: 1,
@@ -135,3 +263,169 @@ valkey_module! {
     data_types: [],
     commands: [],
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use valkey_module::test_shims::create_test_args;
+    use valkey_module::ValkeyArgs;
+
+    #[test]
+    fn parses_positional_options_and_variadic_tail() {
+        let args = ValkeyArgsDerive::from_args(create_test_args(&[
+            "valkey_args_derive",
+            "key",
+            "ex",
+            "10",
+            "nx",
+            "AGGREGATE",
+            "max",
+            "a",
+            "NX",
+        ]))
+        .expect("valid arguments should be parsed");
+
+        assert_eq!(args.key.as_slice(), b"key");
+        assert!(args.nx);
+        assert_eq!(args.ex, Some(10));
+        assert!(matches!(args.aggregate, Some(Aggregate::Max)));
+        assert_eq!(args.members, vec!["a".to_owned(), "NX".to_owned()]);
+    }
+
+    #[test]
+    fn rejects_missing_positional_argument() {
+        let result = ValkeyArgsDerive::from_args(create_test_args(&["valkey_args_derive"]));
+
+        assert!(matches!(result, Err(ValkeyError::WrongArity)));
+    }
+
+    #[test]
+    fn rejects_keyword_without_value() {
+        let result =
+            ValkeyArgsDerive::from_args(create_test_args(&["valkey_args_derive", "key", "EX"]));
+
+        assert!(matches!(result, Err(ValkeyError::Str("ERR syntax error"))));
+    }
+
+    #[derive(ValkeyArgs)]
+    struct ShadowingFields {
+        arg: String,
+        #[ValkeyArgsAttr{keyword: "VALUE"}]
+        value: Option<i64>,
+        #[ValkeyArgsAttr{variadic: true}]
+        args: Vec<String>,
+    }
+
+    #[test]
+    fn parses_fields_named_like_the_generated_locals() {
+        let args = ShadowingFields::from_args(create_test_args(&[
+            "shadowing_fields",
+            "a",
+            "VALUE",
+            "1",
+            "b",
+            "c",
+        ]))
+        .expect("valid arguments should be parsed");
+
+        assert_eq!(args.arg, "a");
+        assert_eq!(args.value, Some(1));
+        assert_eq!(args.args, vec!["b".to_owned(), "c".to_owned()]);
+    }
+
+    #[derive(FromValkeyValue)]
+    struct FromValkeyValueDefaults {
+        name: String,
+        #[ValkeyValueAttr{default: true}]
+        retries: i64,
+        tag: Option<String>,
+    }
+
+    #[derive(FromValkeyValue)]
+    enum Mode {
+        Fast,
+        Custom(i64),
+    }
+
+    #[test]
+    fn round_trips_valkey_value_derive() {
+        let value: ValkeyValue = ValkeyValueEnum::ValkeyValue(ValkeyValueDerive {
+            i: 10,
+            f: 1.1,
+            s: "s".to_owned(),
+            u: 20,
+            v: vec![1, 2, 3],
+            inner: ValkeyValueDeriveInner { i1: 1 },
+            v2: vec![ValkeyValueDeriveInner { i1: 2 }],
+            hash_map: HashMap::from([("key".to_owned(), "val".to_owned())]),
+            hash_set: HashSet::from(["key".to_owned()]),
+            ordered_map: BTreeMap::from([("key".to_owned(), ValkeyValueDeriveInner { i1: 10 })]),
+            ordered_set: BTreeSet::from(["key".to_owned()]),
+        })
+        .into();
+
+        let res = match ValkeyValueEnum::try_from(value) {
+            Ok(ValkeyValueEnum::ValkeyValue(res)) => res,
+            _ => panic!("the struct variant should be built out of a map"),
+        };
+        assert_eq!((res.i, res.f, res.s.as_str(), res.u), (10, 1.1, "s", 20));
+        assert_eq!(res.v, vec![1, 2, 3]);
+        assert_eq!(res.inner.i1, 1);
+        assert_eq!(res.v2[0].i1, 2);
+        assert_eq!(res.hash_map["key"], "val");
+        assert!(res.hash_set.contains("key"));
+        assert_eq!(res.ordered_map["key"].i1, 10);
+        assert!(res.ordered_set.contains("key"));
+
+        assert!(matches!(
+            ValkeyValueEnum::try_from(ValkeyValue::from("OK")),
+            Ok(ValkeyValueEnum::Str(s)) if s == "OK"
+        ));
+    }
+
+    #[test]
+    fn builds_struct_from_flat_array_with_defaults() {
+        let res = FromValkeyValueDefaults::try_from(ValkeyValue::Array(vec![
+            "name".into(),
+            "config".into(),
+        ]))
+        .expect("missing fields should get their defaults");
+
+        assert_eq!(res.name, "config");
+        assert_eq!(res.retries, 0);
+        assert_eq!(res.tag, None);
+
+        assert!(matches!(
+            FromValkeyValueDefaults::try_from(ValkeyValue::Array(vec![
+                "retries".into(),
+                ValkeyValue::Integer(3),
+            ])),
+            Err(ValkeyError::Str("ERR missing field 'name'"))
+        ));
+    }
+
+    #[test]
+    fn builds_enum_from_value() {
+        assert!(matches!(
+            Mode::try_from(ValkeyValue::from("Fast")),
+            Ok(Mode::Fast)
+        ));
+        assert!(matches!(
+            Mode::try_from(ValkeyValue::Integer(5)),
+            Ok(Mode::Custom(5))
+        ));
+        assert!(Mode::try_from(ValkeyValue::Null).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_enum_value() {
+        let result = ValkeyArgsDerive::from_args(create_test_args(&[
+            "valkey_args_derive",
+            "key",
+            "AGGREGATE",
+            "avg",
+        ]));
+
+        assert!(matches!(result, Err(ValkeyError::Str("ERR syntax error"))));
+    }
+}
@@ -0,0 +1,31 @@
+use valkey_module::alloc::ValkeyAlloc;
+use valkey_module::{
+    valkey_module, Context, NextArg, RdbFlags, ValkeyResult, ValkeyString, ValkeyValue,
+};
+
+fn rdb_save(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let path = args.next_string()?;
+    ctx.rdb_save_to(&path, RdbFlags::empty())?;
+    Ok(ValkeyValue::SimpleStringStatic("OK"))
+}
+
+fn rdb_load(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let path = args.next_string()?;
+    ctx.rdb_load_from(&path, RdbFlags::empty())?;
+    Ok(ValkeyValue::SimpleStringStatic("OK"))
+}
+
+//////////////////////////////////////////////////////
+
+valkey_module! {
+    name: "rdb",
+    version: 1,
+    allocator: (ValkeyAlloc, ValkeyAlloc),
+    data_types: [],
+    commands: [
+        ["rdb.save", rdb_save, "admin", 0, 0, 0],
+        ["rdb.load", rdb_load, "admin write", 0, 0, 0],
+    ],
+}
@@ -1,8 +1,9 @@
+use serde::Serialize;
 use std::collections::{BTreeMap, BTreeSet};
 use valkey_module::alloc::ValkeyAlloc;
 use valkey_module::{
-    redisvalue::ValkeyValueKey, valkey_module, Context, NextArg, ValkeyError, ValkeyResult,
-    ValkeyString, ValkeyValue,
+    redisvalue::ValkeyValueKey, valkey_module, CodedError, Context, NextArg, ValkeyError,
+    ValkeyResult, ValkeyString, ValkeyValue,
 };
 
 fn map_mget(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
@@ -60,6 +61,100 @@ fn map_unique(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
     Ok(res)
 }
 
+fn stream_range(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let count = args.next_i64()?;
+    args.done()?;
+
+    ctx.reply_array_streaming(|w| {
+        for i in 0..count {
+            w.push(i)?;
+        }
+        Ok(())
+    })
+}
+
+fn stream_map(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let count = args.next_i64()?;
+    args.done()?;
+
+    ctx.reply_map_streaming(|w| {
+        for i in 0..count {
+            w.push_pair(format!("key{i}"), i)?;
+        }
+        w.push("range")?;
+        w.array(|w| {
+            for i in 0..count {
+                w.push(i)?;
+            }
+            Ok(())
+        })
+    })
+}
+
+fn reply_attributes(_ctx: &Context, _args: Vec<ValkeyString>) -> ValkeyResult {
+    Ok(ValkeyValue::WithAttributes {
+        attrs: BTreeMap::from([(
+            ValkeyValueKey::String("ttl".to_owned()),
+            ValkeyValue::Integer(60),
+        )]),
+        value: Box::new(ValkeyValue::BulkString("value".to_owned())),
+    })
+}
+
+fn reply_push(_ctx: &Context, _args: Vec<ValkeyString>) -> ValkeyResult {
+    Ok(ValkeyValue::Push(vec![
+        ValkeyValue::BulkString("invalidate".to_owned()),
+        ValkeyValue::Integer(1),
+    ]))
+}
+
+fn find_item(name: &str) -> Result<i64, CodedError> {
+    match name {
+        "one" => Ok(1),
+        _ => Err(CodedError::new(
+            "MYMOD_NOTFOUND",
+            format!("no such item '{name}'"),
+        )),
+    }
+}
+
+fn reply_error(_ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let name = args.next_string()?;
+    let item = find_item(&name)?;
+    Ok(item.into())
+}
+
+#[derive(Serialize)]
+enum Status {
+    Active,
+}
+
+#[derive(Serialize)]
+struct Item {
+    name: String,
+    #[serde(rename = "qty")]
+    quantity: u32,
+    #[serde(skip)]
+    _cache: Vec<u8>,
+    tags: Vec<String>,
+    status: Status,
+}
+
+fn reply_serde(_ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let item = Item {
+        name: args.next_string()?,
+        quantity: 3,
+        _cache: Vec::new(),
+        tags: args.map(|s| s.to_string()).collect(),
+        status: Status::Active,
+    };
+    valkey_module::to_value(&item)
+}
+
 //////////////////////////////////////////////////////
 
 valkey_module! {
@@ -70,5 +165,11 @@ valkey_module! {
     commands: [
         ["map.mget", map_mget, "readonly", 1, 1, 1],
         ["map.unique", map_unique, "readonly", 1, 1, 1],
+        ["stream.range", stream_range, "readonly", 0, 0, 0],
+        ["stream.map", stream_map, "readonly", 0, 0, 0],
+        ["reply.attributes", reply_attributes, "readonly", 0, 0, 0],
+        ["reply.push", reply_push, "readonly", 0, 0, 0],
+        ["reply.error", reply_error, "readonly", 0, 0, 0],
+        ["reply.serde", reply_serde, "readonly", 0, 0, 0],
     ],
 }
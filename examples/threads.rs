@@ -78,6 +78,33 @@ fn info_field_on_thread(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult
     Ok(ValkeyValue::NoReply)
 }
 
+fn try_lock_on_thread(ctx: &Context, _args: Vec<ValkeyString>) -> ValkeyResult {
+    // The command holds the GIL until it returns, so the thread can not get it
+    let (locked, locked_in_time) = thread::spawn(|| {
+        let thread_ctx = ThreadSafeContext::new();
+        let locked = thread_ctx.try_lock().is_some();
+        let locked_in_time = thread_ctx.lock_timeout(Duration::from_millis(10)).is_some();
+        (locked, locked_in_time)
+    })
+    .join()
+    .map_err(|_| ValkeyError::Str("ERR the thread panicked"))?;
+
+    // Once the command returned, the GIL is free again
+    let blocked_client = ctx.block_client();
+    let _ = thread::spawn(move || {
+        let thread_ctx = ThreadSafeContext::with_blocked_client(blocked_client);
+        let locked_later = thread_ctx.lock_timeout(Duration::from_secs(1)).is_some();
+        thread_ctx.reply(Ok(vec![
+            i64::from(locked),
+            i64::from(locked_in_time),
+            i64::from(locked_later),
+        ]
+        .into()));
+    });
+
+    Ok(ValkeyValue::NoReply)
+}
+
 //////////////////////////////////////////////////////
 
 valkey_module! {
@@ -91,6 +118,7 @@ valkey_module! {
         ["get_static_data", get_static_data, "", 0, 0, 0],
         ["get_static_data_on_thread", get_static_data_on_thread, "", 0, 0, 0],
         ["info_field_on_thread", info_field_on_thread, "", 0, 0, 0],
+        ["try_lock_on_thread", try_lock_on_thread, "", 0, 0, 0],
     ],
 }
 
@@ -0,0 +1,86 @@
+use lazy_static::lazy_static;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use valkey_module::alloc::ValkeyAlloc;
+use valkey_module::configuration::ConfigurationFlags;
+use valkey_module::workers::Pool;
+use valkey_module::{
+    valkey_module, Context, InfoContext, NextArg, Status, ValkeyError, ValkeyGILGuard,
+    ValkeyResult, ValkeyString, ValkeyValue,
+};
+use valkey_module_macros::info_command_handler;
+
+lazy_static! {
+    static ref THREADS: ValkeyGILGuard<i64> = ValkeyGILGuard::default();
+    static ref QUEUE_DEPTH: ValkeyGILGuard<i64> = ValkeyGILGuard::default();
+}
+
+static POOL: Mutex<Option<Pool>> = Mutex::new(None);
+
+fn init(ctx: &Context, _args: &[ValkeyString]) -> Status {
+    // The configurations are loaded before init, and can not change afterwards
+    let threads = *THREADS.lock(ctx) as usize;
+    let queue_depth = *QUEUE_DEPTH.lock(ctx) as usize;
+    match Pool::new("pool", threads, queue_depth) {
+        Ok(pool) => {
+            POOL.lock().unwrap().replace(pool);
+            Status::Ok
+        }
+        Err(_) => Status::Err,
+    }
+}
+
+fn deinit(_ctx: &Context) -> Status {
+    // No client is blocked anymore, the threads stop right away
+    if let Some(pool) = POOL.lock().unwrap().take() {
+        pool.shutdown();
+    }
+    Status::Ok
+}
+
+/// Sleeps on a worker thread for the given number of milliseconds.
+fn sleep(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+    let mut args = args.into_iter().skip(1);
+    let millis = args.next_u64()?;
+    args.done()?;
+
+    let pool = POOL.lock().unwrap();
+    let pool = pool
+        .as_ref()
+        .ok_or(ValkeyError::Str("ERR the worker pool is not started"))?;
+    pool.submit(ctx.block_client(), move |_thread_ctx| {
+        thread::sleep(Duration::from_millis(millis));
+        Ok(ValkeyValue::Integer(millis as i64))
+    })?;
+
+    Ok(ValkeyValue::NoReply)
+}
+
+#[info_command_handler]
+fn add_info(ctx: &InfoContext, _for_crash_report: bool) -> ValkeyResult<()> {
+    match POOL.lock().unwrap().as_ref() {
+        Some(pool) => pool.add_info(ctx),
+        None => Ok(()),
+    }
+}
+
+//////////////////////////////////////////////////////
+
+valkey_module! {
+    name: "workers",
+    version: 1,
+    allocator: (ValkeyAlloc, ValkeyAlloc),
+    data_types: [],
+    init: init,
+    deinit: deinit,
+    commands: [
+        ["workers.sleep", sleep, "", 0, 0, 0],
+    ],
+    configurations: [
+        i64: [
+            ["threads", &*THREADS, 4, 1, 64, ConfigurationFlags::IMMUTABLE, None],
+            ["queue-depth", &*QUEUE_DEPTH, 64, 1, 1024 * 1024, ConfigurationFlags::IMMUTABLE, None],
+        ],
+    ]
+}
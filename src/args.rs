@@ -0,0 +1,115 @@
+use crate::{ValkeyResult, ValkeyString};
+
+/// Types that can be built from the full argument vector of a command.
+///
+/// Usually implemented with `#[derive(ValkeyArgs)]` from `valkey_module_macros`.
+/// Commands registered with `#[command]` may take any [ValkeyArgs] type as their
+/// second argument, in which case the arguments are parsed before the handler
+/// is invoked and a parsing error is replied to the client directly.
+pub trait ValkeyArgs: Sized {
+    /// Parses the arguments. The first element is the command name and is skipped.
+    fn from_args(args: Vec<ValkeyString>) -> ValkeyResult<Self>;
+}
+
+impl ValkeyArgs for Vec<ValkeyString> {
+    fn from_args(args: Vec<ValkeyString>) -> ValkeyResult<Self> {
+        Ok(args)
+    }
+}
+
+/// The arguments as owned byte strings, which unlike [ValkeyString] can be sent
+/// to other threads, such as the arguments of an async command.
+impl ValkeyArgs for Vec<Vec<u8>> {
+    fn from_args(args: Vec<ValkeyString>) -> ValkeyResult<Self> {
+        Ok(args.into_iter().map(Vec::from).collect())
+    }
+}
+
+/// Types that can be parsed from a single command argument.
+///
+/// Fieldless enums can implement it with `#[derive(ValkeyArgs)]`, which matches
+/// the variant names case-insensitively.
+pub trait FromValkeyArg: Sized {
+    fn from_valkey_arg(arg: ValkeyString) -> ValkeyResult<Self>;
+}
+
+impl FromValkeyArg for ValkeyString {
+    fn from_valkey_arg(arg: ValkeyString) -> ValkeyResult<Self> {
+        Ok(arg)
+    }
+}
+
+impl FromValkeyArg for String {
+    fn from_valkey_arg(arg: ValkeyString) -> ValkeyResult<Self> {
+        arg.try_as_str().map(str::to_owned)
+    }
+}
+
+impl FromValkeyArg for Vec<u8> {
+    fn from_valkey_arg(arg: ValkeyString) -> ValkeyResult<Self> {
+        Ok(arg.into())
+    }
+}
+
+impl FromValkeyArg for i64 {
+    fn from_valkey_arg(arg: ValkeyString) -> ValkeyResult<Self> {
+        arg.parse_integer()
+    }
+}
+
+impl FromValkeyArg for u64 {
+    fn from_valkey_arg(arg: ValkeyString) -> ValkeyResult<Self> {
+        arg.parse_unsigned_integer()
+    }
+}
+
+impl FromValkeyArg for f64 {
+    fn from_valkey_arg(arg: ValkeyString) -> ValkeyResult<Self> {
+        arg.parse_float()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_shims::create_test_args;
+    use crate::ValkeyError;
+
+    #[test]
+    fn parses_scalar_arguments() {
+        let mut args = create_test_args(&["10", "-3", "1.5", "text"]).into_iter();
+
+        assert_eq!(u64::from_valkey_arg(args.next().unwrap()).unwrap(), 10);
+        assert_eq!(i64::from_valkey_arg(args.next().unwrap()).unwrap(), -3);
+        assert_eq!(f64::from_valkey_arg(args.next().unwrap()).unwrap(), 1.5);
+        assert_eq!(
+            String::from_valkey_arg(args.next().unwrap()).unwrap(),
+            "text"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_integer_argument() {
+        let arg = create_test_args(&["abc"]).pop().unwrap();
+
+        assert!(matches!(
+            i64::from_valkey_arg(arg),
+            Err(ValkeyError::Str(_))
+        ));
+    }
+
+    #[test]
+    fn argument_vector_is_passed_through() {
+        let args = Vec::<ValkeyString>::from_args(create_test_args(&["cmd", "a"])).unwrap();
+
+        assert_eq!(args.len(), 2);
+        assert_eq!(args[1].as_slice(), b"a");
+    }
+
+    #[test]
+    fn argument_vector_is_copied_to_bytes() {
+        let args = Vec::<Vec<u8>>::from_args(create_test_args(&["cmd", "a"])).unwrap();
+
+        assert_eq!(args, [b"cmd".to_vec(), b"a".to_vec()]);
+    }
+}
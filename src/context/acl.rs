@@ -0,0 +1,582 @@
+use bitflags::bitflags;
+use std::ffi::CString;
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+use valkey_module_macros_internals::api;
+
+use crate::context::call_builder::CallBuilder;
+use crate::context::{CallOptions, CallOptionsBuilder, StrCallArgs};
+use crate::{raw, CallResult, Context, ValkeyError, ValkeyResult, ValkeyString};
+
+bitflags! {
+    /// The access to a Pub/Sub channel, checked by [Context::acl_check_channel_permission].
+    #[derive(Debug)]
+    pub struct AclChannelFlags : c_int {
+        /// The channel is a pattern, as given to `PSUBSCRIBE`.
+        const PATTERN = raw::REDISMODULE_CMD_CHANNEL_PATTERN as c_int;
+
+        /// User can publish to the channel.
+        const PUBLISH = raw::REDISMODULE_CMD_CHANNEL_PUBLISH as c_int;
+
+        /// User can subscribe to the channel.
+        const SUBSCRIBE = raw::REDISMODULE_CMD_CHANNEL_SUBSCRIBE as c_int;
+
+        /// User can unsubscribe from the channel.
+        const UNSUBSCRIBE = raw::REDISMODULE_CMD_CHANNEL_UNSUBSCRIBE as c_int;
+    }
+}
+
+/// The reason of an entry added to `ACL LOG`, see [Context::acl_add_log_entry].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AclLogEntryReason {
+    /// Authentication failure.
+    Auth,
+    /// Command authorization failure.
+    Command,
+    /// Key authorization failure.
+    Key,
+    /// Channel authorization failure.
+    Channel,
+}
+
+impl From<AclLogEntryReason> for raw::RedisModuleACLLogEntryReason {
+    fn from(reason: AclLogEntryReason) -> Self {
+        match reason {
+            AclLogEntryReason::Auth => raw::RedisModuleACLLogEntryReason_REDISMODULE_ACL_LOG_AUTH,
+            AclLogEntryReason::Command => raw::RedisModuleACLLogEntryReason_REDISMODULE_ACL_LOG_CMD,
+            AclLogEntryReason::Key => raw::RedisModuleACLLogEntryReason_REDISMODULE_ACL_LOG_KEY,
+            AclLogEntryReason::Channel => {
+                raw::RedisModuleACLLogEntryReason_REDISMODULE_ACL_LOG_CHANNEL
+            }
+        }
+    }
+}
+
+/// A set of ACL rules, built one rule at a time, to be applied on a
+/// [ModuleUser] with [ModuleUser::set_rules]. The rules are applied in
+/// the order they were added, with the same semantics as `ACL SETUSER`.
+///
+/// ```rust,no_run,ignore
+/// let rules = AclRules::new()
+///     .on()
+///     .allow_category("read")
+///     .deny_command("keys")
+///     .keys("tenant:1:*");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AclRules {
+    rules: Vec<String>,
+}
+
+impl AclRules {
+    pub fn new() -> AclRules {
+        AclRules::default()
+    }
+
+    /// Adds a raw rule, such as `+get` or `~cache:*`.
+    #[must_use]
+    pub fn rule(mut self, rule: &str) -> AclRules {
+        self.rules.push(rule.to_owned());
+        self
+    }
+
+    /// Removes all the permissions of the user and disables it.
+    #[must_use]
+    pub fn reset(self) -> AclRules {
+        self.rule("reset")
+    }
+
+    /// Enables the user.
+    #[must_use]
+    pub fn on(self) -> AclRules {
+        self.rule("on")
+    }
+
+    /// Disables the user.
+    #[must_use]
+    pub fn off(self) -> AclRules {
+        self.rule("off")
+    }
+
+    /// Allows the given command, or subcommand in the form of `config|get`.
+    #[must_use]
+    pub fn allow_command(self, command: &str) -> AclRules {
+        self.rule(&format!("+{command}"))
+    }
+
+    /// Denies the given command, or subcommand in the form of `config|set`.
+    #[must_use]
+    pub fn deny_command(self, command: &str) -> AclRules {
+        self.rule(&format!("-{command}"))
+    }
+
+    /// Allows all the commands of the given category, such as `read`.
+    #[must_use]
+    pub fn allow_category(self, category: &str) -> AclRules {
+        self.rule(&format!("+@{category}"))
+    }
+
+    /// Denies all the commands of the given category, such as `dangerous`.
+    #[must_use]
+    pub fn deny_category(self, category: &str) -> AclRules {
+        self.rule(&format!("-@{category}"))
+    }
+
+    /// Allows all the commands.
+    #[must_use]
+    pub fn all_commands(self) -> AclRules {
+        self.rule("allcommands")
+    }
+
+    /// Denies all the commands.
+    #[must_use]
+    pub fn no_commands(self) -> AclRules {
+        self.rule("nocommands")
+    }
+
+    /// Allows reading and writing the keys matching the given pattern.
+    #[must_use]
+    pub fn keys(self, pattern: &str) -> AclRules {
+        self.rule(&format!("~{pattern}"))
+    }
+
+    /// Allows reading the keys matching the given pattern.
+    #[must_use]
+    pub fn read_keys(self, pattern: &str) -> AclRules {
+        self.rule(&format!("%R~{pattern}"))
+    }
+
+    /// Allows writing the keys matching the given pattern.
+    #[must_use]
+    pub fn write_keys(self, pattern: &str) -> AclRules {
+        self.rule(&format!("%W~{pattern}"))
+    }
+
+    /// Allows all the keys.
+    #[must_use]
+    pub fn all_keys(self) -> AclRules {
+        self.rule("allkeys")
+    }
+
+    /// Removes all the key patterns previously allowed.
+    #[must_use]
+    pub fn reset_keys(self) -> AclRules {
+        self.rule("resetkeys")
+    }
+
+    /// Allows the Pub/Sub channels matching the given pattern.
+    #[must_use]
+    pub fn channels(self, pattern: &str) -> AclRules {
+        self.rule(&format!("&{pattern}"))
+    }
+
+    /// Allows all the Pub/Sub channels.
+    #[must_use]
+    pub fn all_channels(self) -> AclRules {
+        self.rule("allchannels")
+    }
+
+    /// Removes all the Pub/Sub channel patterns previously allowed.
+    #[must_use]
+    pub fn reset_channels(self) -> AclRules {
+        self.rule("resetchannels")
+    }
+
+    /// Returns the rules, in the order they were added.
+    pub fn rules(&self) -> &[String] {
+        &self.rules
+    }
+
+    /// Verifies that each rule is a single non empty token, so it can't
+    /// be interpreted as more than one rule.
+    fn validate(&self) -> ValkeyResult<()> {
+        match self
+            .rules
+            .iter()
+            .find(|rule| rule.is_empty() || rule.contains(|c: char| c.is_whitespace() || c == '\0'))
+        {
+            Some(rule) => Err(ValkeyError::String(format!(
+                "ERR invalid ACL rule '{rule}'"
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A user created by the module, which is not listed by `ACL LIST` and is
+/// only known to the module. It starts disabled and without any permission,
+/// see [ModuleUser::set_rules]. The user is freed when dropped, which also
+/// disconnects the clients that were authenticated with it.
+#[derive(Debug)]
+pub struct ModuleUser {
+    user: *mut raw::RedisModuleUser,
+}
+
+impl ModuleUser {
+    /// Creates a new user with the given name.
+    pub fn new(name: &str) -> ValkeyResult<ModuleUser> {
+        let name = CString::new(name)
+            .map_err(|_| ValkeyError::Str("ERR user name can not contain a null byte"))?;
+        let user = unsafe { raw::RedisModule_CreateModuleUser.unwrap()(name.as_ptr()) };
+        Ok(ModuleUser { user })
+    }
+
+    /// Applies the given rules, one by one. On error, the rules applied
+    /// before the failing one are kept.
+    pub fn set_rules(&self, rules: &AclRules) -> ValkeyResult<()> {
+        rules.validate()?;
+        rules.rules.iter().try_for_each(|rule| self.set_acl(rule))
+    }
+
+    /// Applies a single rule, such as `+get`.
+    pub fn set_acl(&self, rule: &str) -> ValkeyResult<()> {
+        let invalid = || ValkeyError::String(format!("ERR invalid ACL rule '{rule}'"));
+        let c_rule = CString::new(rule).map_err(|_| invalid())?;
+        let res: raw::Status =
+            unsafe { raw::RedisModule_SetModuleUserACL.unwrap()(self.user, c_rule.as_ptr()) }
+                .into();
+        match res {
+            raw::Status::Ok => Ok(()),
+            raw::Status::Err => Err(invalid()),
+        }
+    }
+
+    /// Applies a whole ACL string, such as `on +get ~cache:*`, as given to
+    /// `ACL SETUSER`. The rules are all applied or none of them is.
+    pub fn set_acl_string(&self, ctx: &Context, acl: &str) -> ValkeyResult<()> {
+        let c_acl = CString::new(acl)
+            .map_err(|_| ValkeyError::Str("ERR ACL string can not contain a null byte"))?;
+        let mut error: *mut raw::RedisModuleString = ptr::null_mut();
+        let res: raw::Status = unsafe {
+            raw::RedisModule_SetModuleUserACLString.unwrap()(
+                ctx.ctx,
+                self.user,
+                c_acl.as_ptr(),
+                &mut error,
+            )
+        }
+        .into();
+        match res {
+            raw::Status::Ok => Ok(()),
+            raw::Status::Err if error.is_null() => Err(ValkeyError::String(format!(
+                "ERR invalid ACL string '{acl}'"
+            ))),
+            raw::Status::Err => {
+                let error = ValkeyString::from_redis_module_string(ctx.ctx, error);
+                Err(ValkeyError::String(format!("ERR {error}")))
+            }
+        }
+    }
+
+    /// Returns the rules of the user, in the format of `ACL GETUSER`.
+    pub fn acl_string(&self) -> ValkeyString {
+        let acl = unsafe { raw::RedisModule_GetModuleUserACLString.unwrap()(self.user) };
+        ValkeyString::from_redis_module_string(ptr::null_mut(), acl)
+    }
+}
+
+impl Drop for ModuleUser {
+    fn drop(&mut self) {
+        unsafe { raw::RedisModule_FreeModuleUser.unwrap()(self.user) };
+    }
+}
+
+/// Attaches the given user to the context, or no user if null.
+fn set_context_user(ctx: &Context, user: *mut raw::RedisModuleUser) {
+    unsafe { raw::RedisModule_SetContextUser.unwrap()(ctx.ctx, user) };
+}
+
+/// Restores the user of the enclosing [UserContext], or no user, once the
+/// callback of a `with_user` returns, also when it panics. The restored user
+/// is borrowed by the enclosing [UserContext], so it outlives the guard.
+struct WithUserGuard<'ctx> {
+    ctx: &'ctx Context,
+    previous: Option<&'ctx ModuleUser>,
+}
+
+impl<'ctx> Drop for WithUserGuard<'ctx> {
+    fn drop(&mut self) {
+        set_context_user(self.ctx, self.previous.map_or(ptr::null_mut(), |u| u.user));
+    }
+}
+
+/// The context given to the callback of [Context::with_user]. The commands
+/// invoked with it are always verified against the permissions of the user,
+/// failing with a `NOPERM` error when the user may not run them.
+pub struct UserContext<'ctx> {
+    ctx: &'ctx Context,
+    user: &'ctx ModuleUser,
+}
+
+impl<'ctx> UserContext<'ctx> {
+    /// Runs the given callback with the context attached to another user,
+    /// then attaches it back to the user of this context.
+    pub fn with_user<R, F: FnOnce(&UserContext) -> R>(&self, user: &ModuleUser, f: F) -> R {
+        run_with_user(self.ctx, user, Some(self.user), f)
+    }
+
+    /// Invokes a command as the user, see [Context::call].
+    pub fn call<'a, T: Into<StrCallArgs<'a>>>(&self, command: &str, args: T) -> ValkeyResult {
+        self.call_ext::<_, CallResult>(command, &CallOptionsBuilder::new().build(), args)
+            .map_or_else(|e| Err(e.into()), |v| Ok((&v).into()))
+    }
+
+    /// Invokes a command as the user, with the given options, see
+    /// [Context::call_ext]. The permissions are verified, and errors returned
+    /// as replies, whatever the options.
+    pub fn call_ext<'a, T: Into<StrCallArgs<'a>>, R: From<CallResult<'static>>>(
+        &self,
+        command: &str,
+        options: &CallOptions,
+        args: T,
+    ) -> R {
+        // Attached again, in case the context was given another user meanwhile
+        set_context_user(self.ctx, self.user.user);
+        self.ctx.call_ext(command, &options.with_flags("CE"), args)
+    }
+
+    /// Starts building a command to invoke as the user, see [Context::cmd].
+    pub fn cmd(&self, command: &str) -> CallBuilder<'ctx> {
+        set_context_user(self.ctx, self.user.user);
+        self.ctx.cmd(command).always_verify_acl()
+    }
+}
+
+fn run_with_user<'ctx, R, F: FnOnce(&UserContext) -> R>(
+    ctx: &'ctx Context,
+    user: &ModuleUser,
+    previous: Option<&'ctx ModuleUser>,
+    f: F,
+) -> R {
+    set_context_user(ctx, user.user);
+    let _guard = WithUserGuard { ctx, previous };
+    f(&UserContext { ctx, user })
+}
+
+impl Context {
+    /// Runs the given callback with the context attached to the given user.
+    /// The commands invoked with the [UserContext] given to the callback are
+    /// verified against the permissions of that user, unlike the commands
+    /// invoked with the context itself. The context is left without a user
+    /// once the callback returns, use [UserContext::with_user] to nest users.
+    ///
+    /// ```rust,no_run,ignore
+    /// let res = ctx.with_user(&user, |ctx| ctx.call("GET", &["key"]));
+    /// ```
+    pub fn with_user<R, F: FnOnce(&UserContext) -> R>(&self, user: &ModuleUser, f: F) -> R {
+        run_with_user(self, user, None, f)
+    }
+
+    /// Authenticates the current client with the given module user, which must
+    /// be enabled. Returns the ID of the client.
+    ///
+    /// The `on_disconnect` callback is called, with the ID of the client, once
+    /// the client is no longer authenticated with the user: when the client
+    /// re-authenticates, disconnects or when the user is freed.
+    pub fn authenticate_client_with_user<F: FnOnce(u64) + 'static>(
+        &self,
+        user: &ModuleUser,
+        on_disconnect: F,
+    ) -> ValkeyResult<u64> {
+        let on_disconnect = Box::into_raw(Box::new(on_disconnect));
+        let mut client_id = 0u64;
+        let res: raw::Status = unsafe {
+            raw::RedisModule_AuthenticateClientWithUser.unwrap()(
+                self.ctx,
+                user.user,
+                Some(user_changed_callback::<F>),
+                on_disconnect.cast::<c_void>(),
+                &mut client_id,
+            )
+        }
+        .into();
+        match res {
+            raw::Status::Ok => Ok(client_id),
+            raw::Status::Err => {
+                drop(unsafe { Box::from_raw(on_disconnect) });
+                Err(ValkeyError::Str("ERR user is disabled"))
+            }
+        }
+    }
+}
+
+pub(crate) mod sealed {
+    use crate::{raw, ValkeyError};
+
+    /// Gives the raw user to the ACL functions. Sealed, as the user is passed
+    /// to the server unchecked.
+    pub trait AclUser {
+        /// Runs the given callback with the user, which is only valid during the call.
+        fn with_acl_user<R>(
+            &self,
+            f: impl FnOnce(*mut raw::RedisModuleUser) -> R,
+        ) -> Result<R, ValkeyError>;
+    }
+}
+
+/// The user given to the ACL functions of [Context], either a [ModuleUser] or
+/// the name of an ACL user, which must exist and be enabled. This trait is
+/// sealed and can not be implemented outside of this crate.
+pub trait AclUser: sealed::AclUser {}
+
+impl AclUser for ModuleUser {}
+
+impl AclUser for ValkeyString {}
+
+impl sealed::AclUser for ModuleUser {
+    fn with_acl_user<R>(
+        &self,
+        f: impl FnOnce(*mut raw::RedisModuleUser) -> R,
+    ) -> Result<R, ValkeyError> {
+        Ok(f(self.user))
+    }
+}
+
+impl sealed::AclUser for ValkeyString {
+    /// The user is looked up by name, and freed once the callback returns.
+    fn with_acl_user<R>(
+        &self,
+        f: impl FnOnce(*mut raw::RedisModuleUser) -> R,
+    ) -> Result<R, ValkeyError> {
+        let user = unsafe { raw::RedisModule_GetModuleUserFromUserName.unwrap()(self.inner) };
+        if user.is_null() {
+            return Err(ValkeyError::Str("User does not exists or disabled"));
+        }
+        let res = f(user);
+        unsafe { raw::RedisModule_FreeModuleUser.unwrap()(user) };
+        Ok(res)
+    }
+}
+
+impl Context {
+    /// Verify that the given user can run the given command, `args` being the
+    /// command name followed by its arguments.
+    /// Return Ok(()) if the user has the permissions or error (with relevant error message)
+    /// if the validation failed.
+    pub fn acl_check_command_permission<U: AclUser>(
+        &self,
+        user: &U,
+        args: &[ValkeyString],
+    ) -> Result<(), ValkeyError> {
+        let mut argv: Vec<*mut raw::RedisModuleString> = args.iter().map(|a| a.inner).collect();
+        let res: raw::Status = user
+            .with_acl_user(|user| unsafe {
+                raw::RedisModule_ACLCheckCommandPermissions.unwrap()(
+                    user,
+                    argv.as_mut_ptr(),
+                    argv.len() as c_int,
+                )
+            })?
+            .into();
+        let res: Result<(), &str> = res.into();
+        res.map_err(|_e| ValkeyError::Str("User does not have permissions on command"))
+    }
+
+    /// Verify that the given user has the given access to the given Pub/Sub channel.
+    /// Return Ok(()) if the user has the permissions or error (with relevant error message)
+    /// if the validation failed.
+    pub fn acl_check_channel_permission<U: AclUser>(
+        &self,
+        user: &U,
+        channel: &ValkeyString,
+        flags: &AclChannelFlags,
+    ) -> Result<(), ValkeyError> {
+        let res: raw::Status = user
+            .with_acl_user(|user| unsafe {
+                raw::RedisModule_ACLCheckChannelPermissions.unwrap()(
+                    user,
+                    channel.inner,
+                    flags.bits(),
+                )
+            })?
+            .into();
+        let res: Result<(), &str> = res.into();
+        res.map_err(|_e| ValkeyError::Str("User does not have permissions on channel"))
+    }
+
+    /// Adds an entry to `ACL LOG`, for the given user, about the given object
+    /// (a command name, a key or a channel, depending on the reason). See
+    /// [Context::acl_add_log_entry_by_user_name] for users that may not exist.
+    pub fn acl_add_log_entry<U: AclUser>(
+        &self,
+        user: &U,
+        object: &ValkeyString,
+        reason: AclLogEntryReason,
+    ) -> Result<(), ValkeyError> {
+        user.with_acl_user(|user| unsafe {
+            raw::RedisModule_ACLAddLogEntry.unwrap()(self.ctx, user, object.inner, reason.into());
+        })
+    }
+
+    api!(
+        [RedisModule_ACLAddLogEntryByUserName],
+        /// Adds an entry to `ACL LOG`, like [Context::acl_add_log_entry], for
+        /// the user of the given name, which does not have to exist.
+        pub fn acl_add_log_entry_by_user_name(
+            &self,
+            user_name: &ValkeyString,
+            object: &ValkeyString,
+            reason: AclLogEntryReason,
+        ) {
+            unsafe {
+                RedisModule_ACLAddLogEntryByUserName(
+                    self.ctx,
+                    user_name.inner,
+                    object.inner,
+                    reason.into(),
+                )
+            };
+        }
+    );
+}
+
+extern "C" fn user_changed_callback<F: FnOnce(u64)>(client_id: u64, privdata: *mut c_void) {
+    let callback = unsafe { Box::from_raw(privdata.cast::<F>()) };
+    callback(client_id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AclRules;
+    use crate::ValkeyError;
+
+    #[test]
+    fn builds_rules_in_order() {
+        let rules = AclRules::new()
+            .reset()
+            .on()
+            .allow_category("read")
+            .deny_command("keys")
+            .allow_command("config|get")
+            .keys("tenant:*")
+            .write_keys("out:*")
+            .channels("news");
+
+        assert_eq!(
+            rules.rules(),
+            [
+                "reset",
+                "on",
+                "+@read",
+                "-keys",
+                "+config|get",
+                "~tenant:*",
+                "%W~out:*",
+                "&news"
+            ]
+        );
+        assert!(rules.validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_rules_that_are_not_a_single_token() {
+        let err = AclRules::new()
+            .allow_command("get")
+            .keys("a* allkeys")
+            .validate()
+            .unwrap_err();
+
+        assert!(matches!(err, ValkeyError::String(message) if message.contains("'~a* allkeys'")));
+        assert!(AclRules::new().rule("").validate().is_err());
+    }
+}
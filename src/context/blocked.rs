@@ -1,17 +1,28 @@
 use crate::redismodule::AUTH_HANDLED;
-use crate::{raw, Context, ValkeyError, ValkeyString};
-use std::os::raw::{c_int, c_void};
+use crate::{raw, Context, ValkeyError, ValkeyResult, ValkeyString};
+use bitflags::bitflags;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::os::raw::{c_int, c_longlong, c_void};
+use std::ptr::{self, NonNull};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use valkey_module_macros_internals::api;
 
 // Callback types for handling blocked client operations
-// Currently supports authentication reply callback for block_client_on_auth
+// Supports the authentication reply callback for block_client_on_auth and the
+// typed reply callback for block_client_with
 #[derive(Debug)]
 pub enum ReplyCallback<T> {
     Auth(fn(&Context, ValkeyString, ValkeyString, Option<&T>) -> Result<c_int, ValkeyError>),
+    Reply(fn(&Context, T) -> ValkeyResult),
 }
 
 #[derive(Debug)]
 struct BlockedClientPrivateData<T: 'static> {
     reply_callback: Option<ReplyCallback<T>>,
+    timeout_callback: Option<TimeoutCallback>,
     free_callback: Option<FreePrivateDataCallback<T>>,
     data: Option<Box<T>>,
 }
@@ -19,11 +30,75 @@ struct BlockedClientPrivateData<T: 'static> {
 // Callback type for freeing private data associated with a blocked client
 type FreePrivateDataCallback<T> = fn(&Context, T);
 
+// Callback type for replying to a blocked client that timed out
+type TimeoutCallback = fn(&Context) -> ValkeyResult;
+
 pub struct BlockedClient<T: 'static = ()> {
     pub(crate) inner: *mut raw::RedisModuleBlockedClient,
     reply_callback: Option<ReplyCallback<T>>,
     free_callback: Option<FreePrivateDataCallback<T>>,
     data: Option<Box<T>>,
+    // Set at block time for the timeout callback, see block_client_with
+    private_data: *mut BlockedClientPrivateData<T>,
+    disconnected: Option<Arc<AtomicBool>>,
+}
+
+/// Tells a background worker whether the client it works for disconnected,
+/// see [BlockedClient::cancellation_token].
+#[derive(Debug, Clone)]
+pub struct CancellationToken {
+    disconnected: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Returns true once the blocked client disconnected, so the work done for
+    /// it can be stopped.
+    pub fn is_cancelled(&self) -> bool {
+        self.disconnected.load(Ordering::Acquire)
+    }
+}
+
+/// Measures the time spent in the background for a blocked client until it is
+/// dropped, see [BlockedClient::measure_time].
+#[must_use]
+pub struct MeasureGuard<'a> {
+    inner: *mut raw::RedisModuleBlockedClient,
+    _blocked_client: PhantomData<&'a ()>,
+}
+
+impl Drop for MeasureGuard<'_> {
+    fn drop(&mut self) {
+        unsafe { raw::RedisModule_BlockedClientMeasureTimeEnd.unwrap()(self.inner) };
+    }
+}
+
+struct DisconnectHandler {
+    disconnected: Arc<AtomicBool>,
+    on_disconnect: Option<Box<dyn FnOnce(&Context) + Send>>,
+}
+
+// The disconnect callback is only given the blocked client, so its handler is
+// looked up by the blocked client pointer.
+fn disconnect_handlers() -> &'static Mutex<HashMap<usize, DisconnectHandler>> {
+    static DISCONNECT_HANDLERS: OnceLock<Mutex<HashMap<usize, DisconnectHandler>>> =
+        OnceLock::new();
+    DISCONNECT_HANDLERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+extern "C" fn disconnect_callback(
+    ctx: *mut raw::RedisModuleCtx,
+    blocked_client: *mut raw::RedisModuleBlockedClient,
+) {
+    let handler = disconnect_handlers()
+        .lock()
+        .unwrap()
+        .remove(&(blocked_client as usize));
+    if let Some(handler) = handler {
+        handler.disconnected.store(true, Ordering::Release);
+        if let Some(on_disconnect) = handler.on_disconnect {
+            on_disconnect(&Context::new(ctx));
+        }
+    }
 }
 
 #[allow(dead_code)]
@@ -47,7 +122,7 @@ unsafe extern "C" fn auth_reply_wrapper<T: 'static>(
 
     let cb = match user_private_data.reply_callback.as_ref() {
         Some(ReplyCallback::Auth(cb)) => cb,
-        None => panic!("[auth_reply_wrapper] Reply callback is null; this should not happen!"),
+        _ => panic!("[auth_reply_wrapper] Reply callback is null; this should not happen!"),
     };
 
     let data_ref = user_private_data.data.as_deref();
@@ -62,6 +137,62 @@ unsafe extern "C" fn auth_reply_wrapper<T: 'static>(
     }
 }
 
+#[allow(dead_code)]
+unsafe extern "C" fn reply_wrapper<T: 'static>(
+    ctx: *mut raw::RedisModuleCtx,
+    _argv: *mut *mut raw::RedisModuleString,
+    _argc: c_int,
+) -> c_int {
+    let context = Context::new(ctx);
+
+    let module_private_data = context.get_blocked_client_private_data();
+    if module_private_data.is_null() {
+        panic!("[reply_wrapper] Module private data is null; this should not happen!");
+    }
+
+    let user_private_data = &mut *(module_private_data as *mut BlockedClientPrivateData<T>);
+
+    let cb = match user_private_data.reply_callback.as_ref() {
+        Some(ReplyCallback::Reply(cb)) => *cb,
+        // Cleared by BlockedClient::abort, the command already replied
+        None => return raw::REDISMODULE_OK as c_int,
+        _ => panic!("[reply_wrapper] Reply callback is not a reply; this should not happen!"),
+    };
+
+    let reply = match user_private_data.data.take() {
+        Some(data) => cb(&context, *data),
+        None => Err(ValkeyError::Str(
+            "ERR the blocked client was unblocked without a result",
+        )),
+    };
+    context.reply(reply);
+    raw::REDISMODULE_OK as c_int
+}
+
+#[allow(dead_code)]
+unsafe extern "C" fn timeout_wrapper<T: 'static>(
+    ctx: *mut raw::RedisModuleCtx,
+    _argv: *mut *mut raw::RedisModuleString,
+    _argc: c_int,
+) -> c_int {
+    let context = Context::new(ctx);
+
+    let module_private_data = context.get_blocked_client_private_data();
+    if module_private_data.is_null() {
+        panic!("[timeout_wrapper] Module private data is null; this should not happen!");
+    }
+
+    // The thread holding the BlockedClient may be setting the data meanwhile, so
+    // only the callback is read here.
+    let cb = match (*(module_private_data as *mut BlockedClientPrivateData<T>)).timeout_callback {
+        Some(cb) => cb,
+        None => panic!("[timeout_wrapper] Timeout callback is null; this should not happen!"),
+    };
+
+    context.reply(cb(&context));
+    raw::REDISMODULE_OK as c_int
+}
+
 #[allow(dead_code)]
 unsafe extern "C" fn free_callback_wrapper<T: 'static>(
     ctx: *mut raw::RedisModuleCtx,
@@ -84,6 +215,93 @@ unsafe extern "C" fn free_callback_wrapper<T: 'static>(
     }
 }
 
+bitflags! {
+    /// Flags for [Context::block_client_on_keys_with_flags].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct BlockOnKeysFlags : c_int {
+        /// Also call the ready callback when one of the keys is deleted, which
+        /// then has to check whether the key still exists.
+        const UNBLOCK_DELETED = raw::REDISMODULE_BLOCK_UNBLOCK_DELETED as c_int;
+    }
+}
+
+/// Called each time one of the keys a client is blocked on is signaled as ready,
+/// with the ready key. Returns `None` to keep the client blocked, or the reply to
+/// unblock the client with.
+pub type KeysReadyCallback<T> = fn(&Context, ValkeyString, &mut T) -> Option<ValkeyResult>;
+
+/// Called when a client blocked on keys times out, returns the reply to unblock
+/// the client with.
+pub type KeysTimeoutCallback<T> = fn(&Context, &mut T) -> ValkeyResult;
+
+struct BlockedOnKeysPrivateData<T> {
+    on_ready: KeysReadyCallback<T>,
+    on_timeout: KeysTimeoutCallback<T>,
+    data: T,
+}
+
+impl<T> BlockedOnKeysPrivateData<T> {
+    fn into_raw(
+        on_ready: KeysReadyCallback<T>,
+        on_timeout: KeysTimeoutCallback<T>,
+        data: T,
+    ) -> *mut c_void {
+        Box::into_raw(Box::new(Self {
+            on_ready,
+            on_timeout,
+            data,
+        }))
+        .cast::<c_void>()
+    }
+
+    /// # Safety
+    /// Must only be called from the reply or timeout callback of a client blocked
+    /// with [Context::block_client_on_keys] with the same `T`.
+    unsafe fn from_context<'a>(ctx: &Context) -> &'a mut Self {
+        let private_data = ctx.get_blocked_client_private_data();
+        if private_data.is_null() {
+            panic!("[BlockedOnKeysPrivateData] Private data is null; this should not happen!");
+        }
+        &mut *private_data.cast::<Self>()
+    }
+}
+
+extern "C" fn keys_ready_wrapper<T>(
+    ctx: *mut raw::RedisModuleCtx,
+    _argv: *mut *mut raw::RedisModuleString,
+    _argc: c_int,
+) -> c_int {
+    let context = Context::new(ctx);
+    let key = unsafe { raw::RedisModule_GetBlockedClientReadyKey.unwrap()(ctx) };
+    if key.is_null() {
+        return raw::REDISMODULE_ERR as c_int;
+    }
+    let key = ValkeyString::new(NonNull::new(ctx), key);
+    let private_data = unsafe { BlockedOnKeysPrivateData::<T>::from_context(&context) };
+    match (private_data.on_ready)(&context, key, &mut private_data.data) {
+        Some(reply) => {
+            context.reply(reply);
+            raw::REDISMODULE_OK as c_int
+        }
+        None => raw::REDISMODULE_ERR as c_int,
+    }
+}
+
+extern "C" fn keys_timeout_wrapper<T>(
+    ctx: *mut raw::RedisModuleCtx,
+    _argv: *mut *mut raw::RedisModuleString,
+    _argc: c_int,
+) -> c_int {
+    let context = Context::new(ctx);
+    let private_data = unsafe { BlockedOnKeysPrivateData::<T>::from_context(&context) };
+    context.reply((private_data.on_timeout)(&context, &mut private_data.data));
+    raw::REDISMODULE_OK as c_int
+}
+
+extern "C" fn keys_free_wrapper<T>(_ctx: *mut raw::RedisModuleCtx, private_data: *mut c_void) {
+    drop(unsafe { Box::from_raw(private_data.cast::<BlockedOnKeysPrivateData<T>>()) });
+}
+
 // We need to be able to send the inner pointer to another thread
 unsafe impl<T> Send for BlockedClient<T> {}
 
@@ -94,6 +312,8 @@ impl<T> BlockedClient<T> {
             reply_callback: None,
             free_callback: None,
             data: None,
+            private_data: ptr::null_mut(),
+            disconnected: None,
         }
     }
 
@@ -116,6 +336,39 @@ impl<T> BlockedClient<T> {
             reply_callback: Some(ReplyCallback::Auth(auth_reply_callback)),
             free_callback,
             data: None,
+            private_data: ptr::null_mut(),
+            disconnected: None,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn with_callbacks(
+        inner: *mut raw::RedisModuleBlockedClient,
+        reply_callback: fn(&Context, T) -> ValkeyResult,
+        timeout_callback: TimeoutCallback,
+        free_callback: Option<FreePrivateDataCallback<T>>,
+    ) -> Self
+    where
+        T: 'static,
+    {
+        // The timeout callback can be called before the client is unblocked, so the
+        // private data is given to the server right away, and only filled on unblock.
+        let private_data = Box::into_raw(Box::new(BlockedClientPrivateData {
+            reply_callback: Some(ReplyCallback::Reply(reply_callback)),
+            timeout_callback: Some(timeout_callback),
+            free_callback,
+            data: None,
+        }));
+        unsafe {
+            raw::RedisModule_BlockClientSetPrivateData.unwrap()(inner, private_data as *mut c_void)
+        };
+        Self {
+            inner,
+            reply_callback: Some(ReplyCallback::Reply(reply_callback)),
+            free_callback,
+            data: None,
+            private_data,
+            disconnected: None,
         }
     }
 
@@ -128,7 +381,9 @@ impl<T> BlockedClient<T> {
     /// * `Ok(())` - If the private data was successfully set
     /// * `Err(ValkeyError)` - If setting the private data failed (e.g., no free callback)
     pub fn set_blocked_private_data(&mut self, data: T) -> Result<(), ValkeyError> {
-        if self.free_callback.is_none() {
+        // A typed reply callback consumes the data, which is otherwise dropped on free
+        let consumed = matches!(self.reply_callback, Some(ReplyCallback::Reply(_)));
+        if self.free_callback.is_none() && !consumed {
             return Err(ValkeyError::Str(
                 "Cannot set private data without a free callback - this would leak memory",
             ));
@@ -137,25 +392,133 @@ impl<T> BlockedClient<T> {
         Ok(())
     }
 
+    /// Unblocks the client with the given result, which is passed to the reply
+    /// callback given to [Context::block_client_with] on the main thread.
+    pub fn unblock(mut self, result: T) {
+        self.data = Some(Box::new(result));
+    }
+
+    fn track_disconnect(&mut self) -> &Arc<AtomicBool> {
+        if self.disconnected.is_none() {
+            let disconnected = Arc::new(AtomicBool::new(false));
+            disconnect_handlers().lock().unwrap().insert(
+                self.inner as usize,
+                DisconnectHandler {
+                    disconnected: Arc::clone(&disconnected),
+                    on_disconnect: None,
+                },
+            );
+            unsafe {
+                raw::RedisModule_SetDisconnectCallback.unwrap()(
+                    self.inner,
+                    Some(disconnect_callback),
+                )
+            };
+            self.disconnected = Some(disconnected);
+        }
+        self.disconnected.as_ref().unwrap()
+    }
+
+    fn untrack_disconnect(&mut self) {
+        if self.disconnected.take().is_some() {
+            disconnect_handlers()
+                .lock()
+                .unwrap()
+                .remove(&(self.inner as usize));
+        }
+    }
+
+    /// Registers a callback, called on the main thread if the client disconnects
+    /// before it is unblocked. The context proves it is called on the main
+    /// thread, usually right after the client was blocked.
+    pub fn on_disconnect<F: FnOnce(&Context) + Send + 'static>(
+        &mut self,
+        _ctx: &Context,
+        on_disconnect: F,
+    ) {
+        self.track_disconnect();
+        if let Some(handler) = disconnect_handlers()
+            .lock()
+            .unwrap()
+            .get_mut(&(self.inner as usize))
+        {
+            handler.on_disconnect = Some(Box::new(on_disconnect));
+        }
+    }
+
+    /// Returns a token telling whether the client disconnected, for the threads
+    /// working for it to stop early. The context proves it is called on the main
+    /// thread, usually right after the client was blocked.
+    pub fn cancellation_token(&mut self, _ctx: &Context) -> CancellationToken {
+        CancellationToken {
+            disconnected: Arc::clone(self.track_disconnect()),
+        }
+    }
+
+    /// Returns true if the client disconnected, provided that
+    /// [BlockedClient::on_disconnect] or [BlockedClient::cancellation_token] was
+    /// called to track its disconnection.
+    pub fn is_disconnected(&self) -> bool {
+        self.disconnected
+            .as_ref()
+            .is_some_and(|disconnected| disconnected.load(Ordering::Acquire))
+    }
+
+    /// Starts measuring the time spent in the background for the client, until
+    /// the returned guard is dropped. The measured time is accounted to the
+    /// command in `SLOWLOG`, `LATENCY` and the command stats, and can be measured
+    /// over several intervals. Returns an error if the time is already measured.
+    pub fn measure_time(&self) -> ValkeyResult<MeasureGuard<'_>> {
+        let res = unsafe { raw::RedisModule_BlockedClientMeasureTimeStart.unwrap()(self.inner) };
+        if res != raw::REDISMODULE_OK as c_int {
+            return Err(ValkeyError::Str(
+                "ERR the time of the blocked client is already measured",
+            ));
+        }
+        Ok(MeasureGuard {
+            inner: self.inner,
+            _blocked_client: PhantomData,
+        })
+    }
+
     /// Aborts the blocked client operation
     ///
     /// # Returns
     /// * `Ok(())` - If the blocked client was successfully aborted
     /// * `Err(ValkeyError)` - If the abort operation failed
     pub fn abort(mut self) -> Result<(), ValkeyError> {
-        unsafe {
-            // Clear references to data and callbacks
-            self.data = None;
-            self.reply_callback = None;
-            self.free_callback = None;
-
-            if raw::RedisModule_AbortBlock.unwrap()(self.inner) == raw::REDISMODULE_OK as c_int {
-                // Prevent the normal Drop from running
-                self.inner = std::ptr::null_mut();
-                Ok(())
-            } else {
-                Err(ValkeyError::Str("Failed to abort blocked client"))
+        // Clear references to data and callbacks
+        self.data = None;
+        self.reply_callback = None;
+        self.free_callback = None;
+
+        if !self.private_data.is_null() {
+            // The timeout callback may still use the private data, so it is
+            // left for the server to free on the main thread, once the client
+            // is unblocked without a reply.
+            self.untrack_disconnect();
+            unsafe {
+                (*self.private_data).reply_callback = None;
+                (*self.private_data).free_callback = None;
+                raw::RedisModule_UnblockClient.unwrap()(
+                    self.inner,
+                    self.private_data as *mut c_void,
+                );
             }
+            // Prevent the normal Drop from running
+            self.inner = std::ptr::null_mut();
+            return Ok(());
+        }
+
+        if unsafe { raw::RedisModule_AbortBlock.unwrap()(self.inner) }
+            == raw::REDISMODULE_OK as c_int
+        {
+            self.untrack_disconnect();
+            // Prevent the normal Drop from running
+            self.inner = std::ptr::null_mut();
+            Ok(())
+        } else {
+            Err(ValkeyError::Str("Failed to abort blocked client"))
         }
     }
 }
@@ -163,10 +526,17 @@ impl<T> BlockedClient<T> {
 impl<T: 'static> Drop for BlockedClient<T> {
     fn drop(&mut self) {
         if !self.inner.is_null() {
-            let callback_data_ptr = if self.reply_callback.is_some() || self.free_callback.is_some()
-            {
+            // The handler must be gone before the blocked client is freed
+            self.untrack_disconnect();
+
+            let callback_data_ptr = if !self.private_data.is_null() {
+                // Only the data is set, the callbacks may be in use by the timeout callback
+                unsafe { (*self.private_data).data = self.data.take() };
+                self.private_data as *mut c_void
+            } else if self.reply_callback.is_some() || self.free_callback.is_some() {
                 Box::into_raw(Box::new(BlockedClientPrivateData {
                     reply_callback: self.reply_callback.take(),
+                    timeout_callback: None,
                     free_callback: self.free_callback.take(),
                     data: self.data.take(),
                 })) as *mut c_void
@@ -233,6 +603,141 @@ impl Context {
         }
     }
 
+    /// Blocks the client until the returned [BlockedClient] is unblocked with a
+    /// result, typically from a worker thread, or until `timeout` expires, where a
+    /// zero `timeout` means the client blocks forever.
+    ///
+    /// `on_reply` is called on the main thread with the result given to
+    /// [BlockedClient::unblock], to format the reply. `on_timeout` is called on
+    /// the main thread to reply once the client timed out. A result that was not
+    /// passed to `on_reply`, because the client timed out or disconnected, is
+    /// passed to `on_free` if given, and dropped otherwise.
+    ///
+    /// ```rust,no_run,ignore
+    /// let blocked_client = ctx.block_client_with(
+    ///     Duration::from_secs(1),
+    ///     |_ctx, sum: u64| Ok(ValkeyValue::Integer(sum as i64)),
+    ///     |_ctx| Err(ValkeyError::Str("ERR timeout")),
+    ///     None,
+    /// );
+    /// thread::spawn(move || blocked_client.unblock(values.iter().sum()));
+    /// Ok(ValkeyValue::NoReply)
+    /// ```
+    #[must_use]
+    #[cfg(all(any(
+        feature = "min-redis-compatibility-version-7-2",
+        feature = "min-valkey-compatibility-version-8-0"
+    ),))]
+    pub fn block_client_with<T: 'static + Send>(
+        &self,
+        timeout: Duration,
+        on_reply: fn(&Context, T) -> ValkeyResult,
+        on_timeout: fn(&Context) -> ValkeyResult,
+        on_free: Option<FreePrivateDataCallback<T>>,
+    ) -> BlockedClient<T> {
+        unsafe {
+            let blocked_client = raw::RedisModule_BlockClient.unwrap()(
+                self.ctx,
+                Some(reply_wrapper::<T>),
+                Some(timeout_wrapper::<T>),
+                Some(free_callback_wrapper::<T>),
+                timeout.as_millis() as c_longlong,
+            );
+
+            BlockedClient::with_callbacks(blocked_client, on_reply, on_timeout, on_free)
+        }
+    }
+
+    /// Returns true when called from the free callback of a blocked client that
+    /// was unblocked because it disconnected.
+    pub fn blocked_client_disconnected(&self) -> bool {
+        unsafe { raw::RedisModule_BlockedClientDisconnected.unwrap()(self.ctx) != 0 }
+    }
+
+    /// Returns true when called from the reply callback of a blocked client.
+    pub fn is_blocked_reply_request(&self) -> bool {
+        unsafe { raw::RedisModule_IsBlockedReplyRequest.unwrap()(self.ctx) != 0 }
+    }
+
+    /// Returns true when called from the timeout callback of a blocked client.
+    pub fn is_blocked_timeout_request(&self) -> bool {
+        unsafe { raw::RedisModule_IsBlockedTimeoutRequest.unwrap()(self.ctx) != 0 }
+    }
+
+    /// Blocks the client until one of the given keys is signaled as ready, which
+    /// the server does when a key of a native type is written to, and modules do
+    /// with [Context::signal_key_as_ready] for their own data types. This is how
+    /// `BLPOP` like commands are implemented.
+    ///
+    /// `on_ready` is called on the main thread, with the ready key, each time one
+    /// of the keys is signaled, until it returns a reply. `on_timeout` is called
+    /// if no reply was returned within `timeout`, where a zero `timeout` means the
+    /// client blocks forever. `private_data` is given to both and is dropped once
+    /// the client is unblocked or disconnects.
+    ///
+    /// The command should then return [crate::ValkeyValue::NoReply].
+    pub fn block_client_on_keys<T: 'static>(
+        &self,
+        keys: &[ValkeyString],
+        timeout: Duration,
+        on_ready: KeysReadyCallback<T>,
+        on_timeout: KeysTimeoutCallback<T>,
+        private_data: T,
+    ) {
+        let mut keys: Vec<_> = keys.iter().map(|key| key.inner).collect();
+        let private_data = BlockedOnKeysPrivateData::into_raw(on_ready, on_timeout, private_data);
+        unsafe {
+            raw::RedisModule_BlockClientOnKeys.unwrap()(
+                self.ctx,
+                Some(keys_ready_wrapper::<T>),
+                Some(keys_timeout_wrapper::<T>),
+                Some(keys_free_wrapper::<T>),
+                timeout.as_millis() as c_longlong,
+                keys.as_mut_ptr(),
+                keys.len() as c_int,
+                private_data,
+            );
+        }
+    }
+
+    api!(
+        [RedisModule_BlockClientOnKeysWithFlags],
+        /// Like [Context::block_client_on_keys], with the given [BlockOnKeysFlags].
+        pub fn block_client_on_keys_with_flags<T: 'static>(
+            &self,
+            keys: &[ValkeyString],
+            timeout: Duration,
+            on_ready: KeysReadyCallback<T>,
+            on_timeout: KeysTimeoutCallback<T>,
+            private_data: T,
+            flags: BlockOnKeysFlags,
+        ) {
+            let mut keys: Vec<_> = keys.iter().map(|key| key.inner).collect();
+            let private_data =
+                BlockedOnKeysPrivateData::into_raw(on_ready, on_timeout, private_data);
+            unsafe {
+                RedisModule_BlockClientOnKeysWithFlags(
+                    self.ctx,
+                    Some(keys_ready_wrapper::<T>),
+                    Some(keys_timeout_wrapper::<T>),
+                    Some(keys_free_wrapper::<T>),
+                    timeout.as_millis() as c_longlong,
+                    keys.as_mut_ptr(),
+                    keys.len() as c_int,
+                    private_data,
+                    flags.bits(),
+                );
+            }
+        }
+    );
+
+    /// Signals that the given key is ready, waking up the clients blocked on it
+    /// with [Context::block_client_on_keys]. The server already does so for keys
+    /// of its native types.
+    pub fn signal_key_as_ready(&self, key: &ValkeyString) {
+        unsafe { raw::RedisModule_SignalKeyAsReady.unwrap()(self.ctx, key.inner) };
+    }
+
     /// Retrieves the private data associated with a blocked client in the current context.
     /// This is an internal function used primarily by reply callbacks to access user-provided data.
     ///
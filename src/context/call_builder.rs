@@ -0,0 +1,218 @@
+use std::borrow::Cow;
+
+use serde::de::DeserializeOwned;
+
+use crate::context::{CallOptions, CallOptionsBuilder};
+use crate::{raw, CallResult, Context, ValkeyError, ValkeyResult, ValkeyString};
+
+/// Types that can be passed as a command argument to [CallBuilder::arg].
+pub trait CallArg {
+    fn to_call_arg(&self) -> Cow<'_, [u8]>;
+}
+
+impl CallArg for str {
+    fn to_call_arg(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.as_bytes())
+    }
+}
+
+impl CallArg for String {
+    fn to_call_arg(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.as_bytes())
+    }
+}
+
+impl CallArg for [u8] {
+    fn to_call_arg(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl CallArg for Vec<u8> {
+    fn to_call_arg(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.as_slice())
+    }
+}
+
+impl CallArg for ValkeyString {
+    fn to_call_arg(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.as_slice())
+    }
+}
+
+impl<T: CallArg + ?Sized> CallArg for &T {
+    fn to_call_arg(&self) -> Cow<'_, [u8]> {
+        (**self).to_call_arg()
+    }
+}
+
+macro_rules! call_arg_number {
+    ($($ty:ty),*) => {
+        $(
+            impl CallArg for $ty {
+                fn to_call_arg(&self) -> Cow<'_, [u8]> {
+                    Cow::Owned(self.to_string().into_bytes())
+                }
+            }
+        )*
+    };
+}
+
+call_arg_number!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+/// Builds and runs a command, see [Context::cmd].
+pub struct CallBuilder<'ctx> {
+    ctx: &'ctx Context,
+    command: String,
+    args: Vec<Vec<u8>>,
+    options: Option<CallOptions>,
+    verify_acl: bool,
+}
+
+impl<'ctx> CallBuilder<'ctx> {
+    /// Adds a single argument.
+    #[must_use]
+    pub fn arg<A: CallArg>(mut self, arg: A) -> Self {
+        self.args.push(arg.to_call_arg().into_owned());
+        self
+    }
+
+    /// Adds all the given arguments.
+    #[must_use]
+    pub fn args<A: CallArg, I: IntoIterator<Item = A>>(mut self, args: I) -> Self {
+        self.args
+            .extend(args.into_iter().map(|arg| arg.to_call_arg().into_owned()));
+        self
+    }
+
+    /// Sets the options the command is invoked with, see [Context::call_ext].
+    #[must_use]
+    pub fn options(mut self, options: CallOptionsBuilder) -> Self {
+        self.options = Some(options.build());
+        self
+    }
+
+    /// Runs the command and converts its reply into `T`. An error reply is
+    /// returned as an error.
+    pub fn query<T>(self) -> ValkeyResult<T>
+    where
+        T: TryFrom<CallResult<'static>>,
+        ValkeyError: From<T::Error>,
+    {
+        let reply = self.call().map_err(ValkeyError::from)?;
+        Ok(T::try_from(Ok(reply))?)
+    }
+
+    /// Runs the command and deserializes its reply into `T`, see [crate::from_call_reply].
+    pub fn query_as<T: DeserializeOwned>(self) -> ValkeyResult<T> {
+        crate::from_call_result(&self.call())
+    }
+
+    /// Runs the command, ignoring its reply unless it is an error.
+    pub fn exec(self) -> ValkeyResult<()> {
+        self.call().map(|_| ()).map_err(ValkeyError::from)
+    }
+
+    /// Verifies the permissions of the user of the context, whatever the
+    /// options, see [crate::UserContext::cmd].
+    pub(crate) fn always_verify_acl(mut self) -> Self {
+        self.verify_acl = true;
+        self
+    }
+
+    fn call(&self) -> CallResult<'static> {
+        let args: Vec<&[u8]> = self.args.iter().map(Vec::as_slice).collect();
+        match (&self.options, self.verify_acl) {
+            (Some(options), true) => {
+                self.ctx
+                    .call_ext(&self.command, &options.with_flags("CE"), args.as_slice())
+            }
+            (None, true) => self.ctx.call_ext(
+                &self.command,
+                &CallOptionsBuilder::new()
+                    .verify_acl()
+                    .errors_as_replies()
+                    .build(),
+                args.as_slice(),
+            ),
+            (Some(options), false) => self.ctx.call_ext(&self.command, options, args.as_slice()),
+            (None, false) => self
+                .ctx
+                .call_internal(&self.command, raw::FMT, args.as_slice()),
+        }
+    }
+}
+
+impl Context {
+    /// Starts building a command to invoke, whose arguments may be of any
+    /// [CallArg] type, such as strings, byte slices, [ValkeyString]s and numbers.
+    ///
+    /// ```rust,no_run,ignore
+    /// let added: ValkeyValue = ctx
+    ///     .cmd("ZADD")
+    ///     .arg(&key)
+    ///     .arg(1.5)
+    ///     .arg("member")
+    ///     .options(CallOptionsBuilder::new().resp(CallOptionResp::Resp3))
+    ///     .query()?;
+    /// ```
+    pub fn cmd<'ctx>(&'ctx self, command: &str) -> CallBuilder<'ctx> {
+        CallBuilder {
+            ctx: self,
+            command: command.to_owned(),
+            args: Vec::new(),
+            options: None,
+            verify_acl: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_shims::create_test_args;
+    use crate::{Context, ValkeyError, ValkeyValue};
+
+    #[test]
+    fn formats_arguments_of_any_type() {
+        let mut context = Context::test();
+        context.expect_call("ZADD", &["z", "1.5", "a", "10"], ValkeyValue::Integer(2));
+        let key = create_test_args(&["z"]).pop().unwrap();
+
+        let res: ValkeyValue = context
+            .cmd("ZADD")
+            .arg(&key)
+            .arg(1.5)
+            .arg(b"a".as_slice())
+            .arg(10u64)
+            .query()
+            .unwrap();
+
+        assert_eq!(res, ValkeyValue::Integer(2));
+    }
+
+    #[test]
+    fn converts_reply_and_errors() {
+        let mut context = Context::test();
+        context.expect_call(
+            "MGET",
+            &["a", "b"],
+            ValkeyValue::Array(vec![
+                ValkeyValue::BulkString("1".to_owned()),
+                ValkeyValue::Null,
+            ]),
+        );
+        context.expect_call(
+            "INCR",
+            &["s"],
+            ValkeyValue::StaticError("ERR value is not an integer or out of range"),
+        );
+
+        let res: Vec<Option<i64>> = context.cmd("MGET").args(["a", "b"]).query_as().unwrap();
+        assert_eq!(res, vec![Some(1), None]);
+
+        let err = context.cmd("INCR").arg("s").exec().unwrap_err();
+        assert!(
+            matches!(err, ValkeyError::String(message) if message.starts_with("ERR value is not"))
+        );
+    }
+}
@@ -116,6 +116,18 @@ pub enum ErrorReply<'root> {
 /// that it is safe to send the underline C data between threads.
 unsafe impl<'root> Send for ErrorCallReply<'root> {}
 
+impl<'root> ErrorCallReply<'root> {
+    /// Return the error as raw RESP protocol bytes, exactly as it was
+    /// returned by the called command.
+    pub fn raw_resp_bytes(&self) -> &[u8] {
+        call_reply_proto(self.reply)
+    }
+
+    pub(crate) fn get_ptr(&self) -> NonNull<RedisModuleCallReply> {
+        self.reply
+    }
+}
+
 impl<'root> ErrorReply<'root> {
     /// Convert [ErrorCallReply] to [String] or [None] if its not a valid utf8.
     pub fn to_utf8_string(&self) -> Option<String> {
@@ -639,6 +651,44 @@ pub enum CallReply<'root> {
     VerbatimString(VerbatimStringCallReply<'root>),
 }
 
+impl<'root> CallReply<'root> {
+    /// Return the underline reply pointer, [None] for [CallReply::Unknown].
+    pub(crate) fn get_ptr(&self) -> Option<NonNull<RedisModuleCallReply>> {
+        match self {
+            CallReply::Unknown => None,
+            CallReply::I64(inner) => Some(inner.reply),
+            CallReply::String(inner) => Some(inner.reply),
+            CallReply::Array(inner) => Some(inner.reply),
+            CallReply::Null(inner) => Some(inner.reply),
+            CallReply::Map(inner) => Some(inner.reply),
+            CallReply::Set(inner) => Some(inner.reply),
+            CallReply::Bool(inner) => Some(inner.reply),
+            CallReply::Double(inner) => Some(inner.reply),
+            CallReply::BigNumber(inner) => Some(inner.reply),
+            CallReply::VerbatimString(inner) => Some(inner.reply),
+        }
+    }
+
+    /// Return the reply as raw RESP protocol bytes, exactly as it was
+    /// returned by the called command. Empty for [CallReply::Unknown].
+    pub fn raw_resp_bytes(&self) -> &[u8] {
+        match self.get_ptr() {
+            Some(reply) => call_reply_proto(reply),
+            None => &[],
+        }
+    }
+}
+
+/// Return the RESP protocol of the given reply, wrapper for RedisModule_CallReplyProto.
+fn call_reply_proto<'a>(reply: NonNull<RedisModuleCallReply>) -> &'a [u8] {
+    let mut len: usize = 0;
+    let proto = unsafe { RedisModule_CallReplyProto.unwrap()(reply.as_ptr(), &mut len) };
+    if proto.is_null() {
+        return &[];
+    }
+    unsafe { slice::from_raw_parts(proto.cast::<u8>(), len) }
+}
+
 /// Send implementation to [CallReply].
 /// We need to implements this trait because [CallReply] hold
 /// raw pointers to C data which does not auto implement the [Send] trait.
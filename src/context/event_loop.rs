@@ -0,0 +1,120 @@
+use bitflags::bitflags;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::ffi::c_void;
+use std::os::raw::c_int;
+use std::os::unix::io::RawFd;
+use std::rc::Rc;
+use std::sync::atomic::Ordering;
+
+use crate::{raw, Context, ValkeyError, ValkeyResult};
+
+bitflags! {
+    /// The events to wait for on a file descriptor, see
+    /// [Context::event_loop_register].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct EventLoopMask : c_int {
+        /// The file descriptor can be read from.
+        const READABLE = raw::REDISMODULE_EVENTLOOP_READABLE as c_int;
+        /// The file descriptor can be written to.
+        const WRITABLE = raw::REDISMODULE_EVENTLOOP_WRITABLE as c_int;
+    }
+}
+
+type EventLoopCallback = Rc<RefCell<Box<dyn FnMut(&Context, EventLoopMask)>>>;
+
+thread_local! {
+    /// The file descriptors with a live [EventLoopHandle]. The server keeps a
+    /// single callback per file descriptor, so registering one twice would
+    /// leave a registration pointing at the callback freed by the other handle.
+    static REGISTERED_FDS: RefCell<HashSet<RawFd>> = RefCell::new(HashSet::new());
+}
+
+/// A file descriptor registered in the event loop of the server with
+/// [Context::event_loop_register]. Dropping the handle removes it from the
+/// event loop, which must happen on the main thread.
+pub struct EventLoopHandle {
+    fd: RawFd,
+    mask: EventLoopMask,
+    user_data: *mut EventLoopCallback,
+}
+
+impl EventLoopHandle {
+    /// Returns the registered file descriptor.
+    pub fn fd(&self) -> RawFd {
+        self.fd
+    }
+
+    /// Returns the events the file descriptor is registered for.
+    pub fn mask(&self) -> EventLoopMask {
+        self.mask
+    }
+}
+
+impl Drop for EventLoopHandle {
+    fn drop(&mut self) {
+        unsafe {
+            raw::RedisModule_EventLoopDel.unwrap()(self.fd, self.mask.bits());
+            // The callback may still run, when the handle is dropped from it
+            drop(Box::from_raw(self.user_data));
+        }
+        REGISTERED_FDS.with(|fds| fds.borrow_mut().remove(&self.fd));
+    }
+}
+
+extern "C" fn event_loop_callback(_fd: c_int, user_data: *mut c_void, mask: c_int) {
+    let callback = Rc::clone(unsafe { &*user_data.cast::<EventLoopCallback>() });
+    let ctx = Context::new(crate::MODULE_CONTEXT.ctx.load(Ordering::Relaxed));
+    let mut callback = callback.borrow_mut();
+    callback(&ctx, EventLoopMask::from_bits_truncate(mask));
+}
+
+impl Context {
+    /// Registers the file descriptor in the event loop of the server, calling
+    /// `callback` on the main thread whenever it is ready for one of the events
+    /// of `mask`. The callback receives the events the descriptor is ready for.
+    ///
+    /// The server keeps a single callback per file descriptor, so registering
+    /// a file descriptor again while its handle is alive fails, register it for
+    /// both events at once instead.
+    pub fn event_loop_register<F>(
+        &self,
+        fd: RawFd,
+        mask: EventLoopMask,
+        callback: F,
+    ) -> ValkeyResult<EventLoopHandle>
+    where
+        F: FnMut(&Context, EventLoopMask) + 'static,
+    {
+        if !REGISTERED_FDS.with(|fds| fds.borrow_mut().insert(fd)) {
+            return Err(ValkeyError::String(format!(
+                "ERR file descriptor {fd} is already registered in the event loop"
+            )));
+        }
+        let callback: EventLoopCallback = Rc::new(RefCell::new(Box::new(callback)));
+        let user_data = Box::into_raw(Box::new(callback));
+        let res = unsafe {
+            raw::RedisModule_EventLoopAdd.unwrap()(
+                fd,
+                mask.bits(),
+                Some(event_loop_callback),
+                user_data.cast::<c_void>(),
+            )
+        };
+        match raw::Status::from(res) {
+            raw::Status::Ok => Ok(EventLoopHandle {
+                fd,
+                mask,
+                user_data,
+            }),
+            raw::Status::Err => {
+                let error = std::io::Error::last_os_error();
+                drop(unsafe { Box::from_raw(user_data) });
+                REGISTERED_FDS.with(|fds| fds.borrow_mut().remove(&fd));
+                Err(ValkeyError::String(format!(
+                    "ERR failed registering the file descriptor in the event loop: {error}"
+                )))
+            }
+        }
+    }
+}
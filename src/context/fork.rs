@@ -0,0 +1,159 @@
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::os::raw::c_int;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+use std::sync::atomic::Ordering;
+
+use crate::{raw, Context, ValkeyError, ValkeyResult};
+
+/// How a fork child created with [Context::fork] ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForkExitStatus {
+    /// The child exited with the given exit code. The child exits with 0
+    /// when its callback succeeds and with 1 when it fails or panics.
+    Exited(i32),
+    /// The child was terminated by the given signal.
+    Signaled(i32),
+    /// The child was killed with [ForkHandle::kill].
+    Killed,
+}
+
+impl ForkExitStatus {
+    /// Returns true if the child exited with a 0 exit code.
+    pub fn success(&self) -> bool {
+        matches!(self, ForkExitStatus::Exited(0))
+    }
+}
+
+type DoneCallback = Rc<RefCell<Option<Box<dyn FnOnce(&Context, ForkExitStatus)>>>>;
+
+/// Given to the callback running in the fork child, see [Context::fork].
+pub struct ForkChild {
+    _private: (),
+}
+
+impl ForkChild {
+    /// Reports the progress of the child to the parent, from 0 to 1, or -1 if
+    /// unknown. The progress is shown in `INFO` as `current_fork_perc`.
+    pub fn heartbeat(&self, progress: f64) {
+        unsafe { raw::RedisModule_SendChildHeartbeat.unwrap()(progress) };
+    }
+}
+
+/// A fork child created with [Context::fork]. Dropping the handle does not
+/// affect the child.
+pub struct ForkHandle {
+    pid: i32,
+    on_done: DoneCallback,
+    user_data: *mut DoneCallback,
+}
+
+impl ForkHandle {
+    /// Returns the process ID of the child.
+    pub fn pid(&self) -> i32 {
+        self.pid
+    }
+
+    /// Returns true while the child runs, until its `on_done` callback was called.
+    pub fn is_running(&self) -> bool {
+        self.on_done.borrow().is_some()
+    }
+
+    /// Kills the child and waits for it to terminate. The `on_done` callback
+    /// is called with [ForkExitStatus::Killed] before this function returns.
+    /// Returns an error if the child is no longer running.
+    pub fn kill(&self, ctx: &Context) -> ValkeyResult<()> {
+        if !self.is_running() {
+            return Err(ValkeyError::Str("ERR fork child is not running"));
+        }
+        let res: raw::Status = unsafe { raw::RedisModule_KillForkChild.unwrap()(self.pid) }.into();
+        if res == raw::Status::Err {
+            return Err(ValkeyError::Str("ERR fork child is not running"));
+        }
+        // The done handler will not be called for a killed child, so the data given
+        // to it is freed here.
+        drop(unsafe { Box::from_raw(self.user_data) });
+        let on_done = self.on_done.borrow_mut().take();
+        if let Some(on_done) = on_done {
+            on_done(ctx, ForkExitStatus::Killed);
+        }
+        Ok(())
+    }
+}
+
+impl Context {
+    /// Forks the server process and runs `child_fn` in the child, which sees
+    /// a copy-on-write snapshot of the memory at the time of the fork. The child
+    /// exits once `child_fn` returns. Only a single fork child, of any kind
+    /// (including `BGSAVE`), can run at a time.
+    ///
+    /// `on_done` is called on the main thread, with the exit status of the
+    /// child, once it ends.
+    ///
+    /// ```rust,no_run,ignore
+    /// let handle = ctx.fork(
+    ///     |child| {
+    ///         for (i, chunk) in index.chunks().enumerate() {
+    ///             write_chunk(chunk)?;
+    ///             child.heartbeat(i as f64 / index.len() as f64);
+    ///         }
+    ///         Ok(())
+    ///     },
+    ///     |ctx, status| ctx.log_notice(&format!("snapshot done: {status:?}")),
+    /// )?;
+    /// ```
+    pub fn fork<C, D>(&self, child_fn: C, on_done: D) -> ValkeyResult<ForkHandle>
+    where
+        C: FnOnce(&ForkChild) -> ValkeyResult<()>,
+        D: FnOnce(&Context, ForkExitStatus) + 'static,
+    {
+        let on_done: DoneCallback = Rc::new(RefCell::new(Some(Box::new(on_done))));
+        let user_data = Box::into_raw(Box::new(Rc::clone(&on_done)));
+        let pid = unsafe {
+            raw::RedisModule_Fork.unwrap()(Some(fork_done_handler), user_data.cast::<c_void>())
+        };
+        match pid {
+            -1 => {
+                drop(unsafe { Box::from_raw(user_data) });
+                Err(ValkeyError::String(format!(
+                    "ERR fork failed: {}",
+                    std::io::Error::last_os_error()
+                )))
+            }
+            0 => {
+                let res =
+                    panic::catch_unwind(AssertUnwindSafe(|| child_fn(&ForkChild { _private: () })));
+                let code = match res {
+                    Ok(Ok(())) => 0,
+                    Ok(Err(e)) => {
+                        self.log_warning(&format!("Fork child failed: {e}"));
+                        1
+                    }
+                    Err(_) => 1,
+                };
+                unsafe { raw::RedisModule_ExitFromChild.unwrap()(code) };
+                unreachable!("RedisModule_ExitFromChild returned")
+            }
+            pid => Ok(ForkHandle {
+                pid,
+                on_done,
+                user_data,
+            }),
+        }
+    }
+}
+
+extern "C" fn fork_done_handler(exitcode: c_int, bysignal: c_int, user_data: *mut c_void) {
+    let on_done = unsafe { Box::from_raw(user_data.cast::<DoneCallback>()) };
+    let status = if bysignal != 0 {
+        ForkExitStatus::Signaled(bysignal)
+    } else {
+        ForkExitStatus::Exited(exitcode)
+    };
+    let ctx = Context::new(crate::MODULE_CONTEXT.ctx.load(Ordering::Relaxed));
+    let callback = on_done.borrow_mut().take();
+    if let Some(callback) = callback {
+        callback(&ctx, status);
+    }
+}
@@ -1,4 +1,5 @@
 use bitflags::bitflags;
+use serde::de::DeserializeOwned;
 use std::collections::{BTreeMap, HashMap};
 use std::ffi::CString;
 use std::os::raw::c_void;
@@ -24,19 +25,31 @@ use std::ops::Deref;
 
 use std::ffi::CStr;
 
-use self::call_reply::{create_promise_call_reply, CallResult, PromiseCallReply};
+use self::call_reply::{
+    create_promise_call_reply, CallReply, CallResult, ErrorReply, PromiseCallReply,
+};
 use self::thread_safe::ValkeyLockIndicator;
 
 mod timer;
 
+pub mod acl;
 pub mod auth;
 pub mod blocked;
+pub mod call_builder;
 pub mod call_reply;
 pub mod client;
 pub mod commands;
+pub mod event_loop;
 pub mod filter;
+pub mod fork;
 pub mod info;
 pub mod keys_cursor;
+#[cfg(all(any(
+    feature = "min-valkey-compatibility-version-8-0",
+    feature = "min-redis-compatibility-version-7-2"
+)))]
+pub mod rdb;
+pub mod reply;
 pub mod server_events;
 pub mod thread_safe;
 
@@ -57,6 +70,17 @@ pub struct CallOptions {
     options: CString,
 }
 
+impl CallOptions {
+    /// Returns the options with the given flags added.
+    pub(crate) fn with_flags(&self, flags: &str) -> CallOptions {
+        let mut options = self.options.as_bytes().to_vec();
+        options.extend_from_slice(flags.as_bytes());
+        CallOptions {
+            options: CString::new(options).unwrap(),
+        }
+    }
+}
+
 #[derive(Clone)]
 #[cfg(all(any(
     feature = "min-valkey-compatibility-version-8-0",
@@ -402,6 +426,15 @@ impl Context {
         }
     }
 
+    /// Redacts the argument at the given position of the current command, so it is
+    /// replaced with `(redacted)` in the slowlog and the `MONITOR` output.
+    /// The command name, at position 0, can not be redacted.
+    /// Returns [Status::Err] if the position is out of range.
+    pub fn redact_arg(&self, pos: i32) -> Status {
+        unsafe { raw::RedisModule_RedactClientCommandArgument.unwrap()(self.ctx, pos as c_int) }
+            .into()
+    }
+
     fn call_internal<
         'ctx,
         'a,
@@ -444,6 +477,20 @@ impl Context {
             .map_or_else(|e| Err(e.into()), |v| Ok((&v).into()))
     }
 
+    /// Invoke a command on Valkey and convert its reply into `T`, see [crate::from_call_reply].
+    ///
+    /// ```rust,no_run,ignore
+    /// let scores: Vec<(String, f64)> = ctx.call_typed("ZRANGE", &["z", "0", "-1", "WITHSCORES"])?;
+    /// ```
+    pub fn call_typed<'a, T: DeserializeOwned, A: Into<StrCallArgs<'a>>>(
+        &self,
+        command: &str,
+        args: A,
+    ) -> ValkeyResult<T> {
+        let res: CallResult = self.call_internal(command, raw::FMT, args);
+        crate::de::from_call_result(&res)
+    }
+
     /// Invoke a command on Valkey and return the result
     /// Unlike 'call' this API also allow to pass a CallOption to control different aspects
     /// of the command invocation.
@@ -502,6 +549,35 @@ impl Context {
         unsafe { raw::RedisModule_ReplyWithError.unwrap()(self.ctx, msg.as_ptr()).into() }
     }
 
+    /// Replies with an error made of the given error code and message, such as
+    /// `MYMOD_NOTFOUND no such item`. The code is counted in `INFO errorstats`.
+    /// Uses RedisModule_ReplyWithErrorFormat when available.
+    #[allow(clippy::must_use_candidate)]
+    pub fn reply_error_code(&self, code: &str, message: &str) -> raw::Status {
+        #[cfg(all(any(
+            feature = "min-redis-compatibility-version-7-2",
+            feature = "min-valkey-compatibility-version-8-0"
+        ),))]
+        {
+            let code = Self::str_as_legal_resp_string(code);
+            let message = Self::str_as_legal_resp_string(message);
+            unsafe {
+                raw::RedisModule_ReplyWithErrorFormat.unwrap()(
+                    self.ctx,
+                    b"%s %s\0".as_ptr().cast::<c_char>(),
+                    code.as_ptr(),
+                    message.as_ptr(),
+                )
+                .into()
+            }
+        }
+        #[cfg(not(any(
+            feature = "min-redis-compatibility-version-7-2",
+            feature = "min-valkey-compatibility-version-8-0"
+        )))]
+        self.reply_error_string(&format!("{code} {message}"))
+    }
+
     #[cfg(feature = "min-valkey-compatibility-version-8-0")]
     pub fn add_acl_category(&self, s: &str) -> raw::Status {
         let acl_flags = Self::str_as_legal_resp_string(s);
@@ -609,6 +685,17 @@ impl Context {
                 raw::Status::Ok
             }
 
+            Ok(ValkeyValue::MapEntries(entries)) => {
+                raw::reply_with_map(self.ctx, entries.len() as c_long);
+
+                for (key, value) in entries {
+                    self.reply_with_key(key);
+                    self.reply(Ok(value));
+                }
+
+                raw::Status::Ok
+            }
+
             Ok(ValkeyValue::Set(set)) => {
                 raw::reply_with_set(self.ctx, set.len() as c_long);
                 set.into_iter().for_each(|e| {
@@ -627,6 +714,36 @@ impl Context {
                 raw::Status::Ok
             }
 
+            Ok(ValkeyValue::WithAttributes { attrs, value }) => {
+                // Attributes are rejected on RESP2, in which case only the value is sent.
+                if raw::reply_with_attribute(self.ctx, attrs.len() as c_long) == raw::Status::Ok {
+                    for (key, value) in attrs {
+                        self.reply_with_key(key);
+                        self.reply(Ok(value));
+                    }
+                }
+
+                self.reply(Ok(*value))
+            }
+
+            Ok(ValkeyValue::Push(array)) => {
+                // A push message is an array whose type is changed once its length is set.
+                raw::reply_with_array(self.ctx, raw::POSTPONED_LEN);
+                let len = array.len() as c_long;
+
+                for elem in array {
+                    self.reply(Ok(elem));
+                }
+
+                if self.get_flags().contains(ContextFlags::FLAGS_RESP3) {
+                    raw::reply_set_push_length(self.ctx, len);
+                } else {
+                    raw::reply_set_array_length(self.ctx, len);
+                }
+
+                raw::Status::Ok
+            }
+
             Ok(ValkeyValue::Null) => raw::reply_with_null(self.ctx),
 
             Ok(ValkeyValue::NoReply) => raw::Status::Ok,
@@ -649,6 +766,32 @@ impl Context {
             Err(ValkeyError::String(s)) => self.reply_error_string(s.as_str()),
 
             Err(ValkeyError::Str(s)) => self.reply_error_string(s),
+
+            Err(ValkeyError::Coded(e)) => self.reply_error_code(e.code(), e.message()),
+        }
+    }
+
+    /// Replies with the given [CallReply] as is, without converting it to a
+    /// [ValkeyValue], so reply types such as attributes, verbatim strings and
+    /// big numbers are kept. Wrapper for RedisModule_ReplyWithCallReply.
+    pub fn reply_with_call_reply(&self, reply: &CallReply) -> raw::Status {
+        match reply.get_ptr() {
+            Some(reply) => unsafe {
+                raw::RedisModule_ReplyWithCallReply.unwrap()(self.ctx, reply.as_ptr()).into()
+            },
+            None => raw::Status::Err,
+        }
+    }
+
+    /// Same as [Self::reply_with_call_reply], but also forwards errors.
+    pub fn reply_with_call_result(&self, result: &CallResult) -> raw::Status {
+        match result {
+            Ok(reply) => self.reply_with_call_reply(reply),
+            Err(ErrorReply::RedisError(reply)) => unsafe {
+                raw::RedisModule_ReplyWithCallReply.unwrap()(self.ctx, reply.get_ptr().as_ptr())
+                    .into()
+            },
+            Err(ErrorReply::Message(s)) => self.reply_error_string(s),
         }
     }
 
@@ -818,25 +961,21 @@ impl Context {
     /// Verify the the given user has the give ACL permission on the given key.
     /// Return Ok(()) if the user has the permissions or error (with relevant error message)
     /// if the validation failed.
-    pub fn acl_check_key_permission(
+    pub fn acl_check_key_permission<U: acl::AclUser>(
         &self,
-        user_name: &ValkeyString,
+        user: &U,
         key_name: &ValkeyString,
         permissions: &AclPermissions,
     ) -> Result<(), ValkeyError> {
-        let user = unsafe { raw::RedisModule_GetModuleUserFromUserName.unwrap()(user_name.inner) };
-        if user.is_null() {
-            return Err(ValkeyError::Str("User does not exists or disabled"));
-        }
-        let acl_permission_result: raw::Status = unsafe {
-            raw::RedisModule_ACLCheckKeyPermissions.unwrap()(
-                user,
-                key_name.inner,
-                permissions.bits(),
-            )
-        }
-        .into();
-        unsafe { raw::RedisModule_FreeModuleUser.unwrap()(user) };
+        let acl_permission_result: raw::Status = user
+            .with_acl_user(|user| unsafe {
+                raw::RedisModule_ACLCheckKeyPermissions.unwrap()(
+                    user,
+                    key_name.inner,
+                    permissions.bits(),
+                )
+            })?
+            .into();
         let acl_permission_result: Result<(), &str> = acl_permission_result.into();
         acl_permission_result
             .map_err(|_e| ValkeyError::Str("User does not have permissions on key"))
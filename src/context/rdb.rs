@@ -0,0 +1,81 @@
+use bitflags::bitflags;
+use std::ffi::CString;
+use std::os::raw::c_int;
+use std::ptr::NonNull;
+
+use crate::{raw, Context, ValkeyError, ValkeyResult};
+
+bitflags! {
+    /// Flags for [Context::rdb_load] and [Context::rdb_save]. No flag is
+    /// currently defined by the server, so only [RdbFlags::empty] is valid.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct RdbFlags : c_int {
+    }
+}
+
+/// An RDB stream, to load the dataset from or to save it to, which is freed
+/// when dropped. Only file streams are currently supported by the server.
+#[derive(Debug)]
+pub struct RdbStream {
+    stream: NonNull<raw::RedisModuleRdbStream>,
+}
+
+impl RdbStream {
+    /// Creates a stream reading from or writing to the given RDB file.
+    pub fn from_file(path: &str) -> ValkeyResult<RdbStream> {
+        let path = CString::new(path)
+            .map_err(|_| ValkeyError::Str("ERR path can not contain a null byte"))?;
+        let stream = unsafe { raw::RedisModule_RdbStreamCreateFromFile.unwrap()(path.as_ptr()) };
+        NonNull::new(stream)
+            .map(|stream| RdbStream { stream })
+            .ok_or(ValkeyError::Str("ERR failed creating the RDB stream"))
+    }
+}
+
+impl Drop for RdbStream {
+    fn drop(&mut self) {
+        unsafe { raw::RedisModule_RdbStreamFree.unwrap()(self.stream.as_ptr()) };
+    }
+}
+
+fn rdb_result(res: c_int, action: &str) -> ValkeyResult<()> {
+    match raw::Status::from(res) {
+        raw::Status::Ok => Ok(()),
+        raw::Status::Err => Err(ValkeyError::String(format!(
+            "ERR failed {action} the RDB: {}",
+            std::io::Error::last_os_error()
+        ))),
+    }
+}
+
+impl Context {
+    /// Replaces the whole dataset with the content of the given RDB stream.
+    /// Not supported on a replica, or while the server is loading, running a
+    /// script or a `MULTI`.
+    pub fn rdb_load(&self, stream: &RdbStream, flags: RdbFlags) -> ValkeyResult<()> {
+        let res = unsafe {
+            raw::RedisModule_RdbLoad.unwrap()(self.ctx, stream.stream.as_ptr(), flags.bits())
+        };
+        rdb_result(res, "loading")
+    }
+
+    /// Saves the dataset to the given RDB stream, synchronously.
+    pub fn rdb_save(&self, stream: &RdbStream, flags: RdbFlags) -> ValkeyResult<()> {
+        let res = unsafe {
+            raw::RedisModule_RdbSave.unwrap()(self.ctx, stream.stream.as_ptr(), flags.bits())
+        };
+        rdb_result(res, "saving")
+    }
+
+    /// Replaces the whole dataset with the content of the RDB file at the
+    /// given path, see [Context::rdb_load].
+    pub fn rdb_load_from(&self, path: &str, flags: RdbFlags) -> ValkeyResult<()> {
+        self.rdb_load(&RdbStream::from_file(path)?, flags)
+    }
+
+    /// Saves the dataset to an RDB file at the given path, synchronously,
+    /// see [Context::rdb_save].
+    pub fn rdb_save_to(&self, path: &str, flags: RdbFlags) -> ValkeyResult<()> {
+        self.rdb_save(&RdbStream::from_file(path)?, flags)
+    }
+}
@@ -0,0 +1,229 @@
+use std::os::raw::c_long;
+
+use crate::redisvalue::ValkeyValueKey;
+use crate::{raw, Context, ValkeyError, ValkeyResult, ValkeyValue};
+
+/// The aggregate type written by a [ReplyWriter].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Aggregate {
+    Array,
+    Map,
+    Set,
+    Attribute,
+}
+
+impl Aggregate {
+    fn start(self, ctx: &Context) -> raw::Status {
+        match self {
+            Aggregate::Array => raw::reply_with_array(ctx.ctx, raw::POSTPONED_LEN),
+            Aggregate::Map => raw::reply_with_map(ctx.ctx, raw::POSTPONED_LEN),
+            Aggregate::Set => raw::reply_with_set(ctx.ctx, raw::POSTPONED_LEN),
+            Aggregate::Attribute => raw::reply_with_attribute(ctx.ctx, raw::POSTPONED_LEN),
+        }
+    }
+
+    /// Sets the length of the aggregate, `len` being the number of replies written into it.
+    fn finish(self, ctx: &Context, len: c_long) {
+        match self {
+            Aggregate::Array => raw::reply_set_array_length(ctx.ctx, len),
+            Aggregate::Map => raw::reply_set_map_length(ctx.ctx, len / 2),
+            Aggregate::Set => raw::reply_set_set_length(ctx.ctx, len),
+            Aggregate::Attribute => raw::reply_set_attribute_length(ctx.ctx, len / 2),
+        }
+    }
+
+    fn is_map(self) -> bool {
+        matches!(self, Aggregate::Map | Aggregate::Attribute)
+    }
+}
+
+/// Writes the elements of an aggregate reply (array, map, set or attribute)
+/// one by one, as they are produced, without knowing their number in advance.
+/// The length of the aggregate is set once all the elements were written.
+///
+/// Maps and attributes are written as a sequence of keys and values, using
+/// [ReplyWriter::push_pair] or consecutive calls to [ReplyWriter::push].
+pub struct ReplyWriter<'ctx> {
+    ctx: &'ctx Context,
+    aggregate: Aggregate,
+    len: c_long,
+}
+
+impl<'ctx> ReplyWriter<'ctx> {
+    fn new(ctx: &'ctx Context, aggregate: Aggregate) -> Self {
+        Self {
+            ctx,
+            aggregate,
+            len: 0,
+        }
+    }
+
+    fn written(&mut self, status: raw::Status) -> ValkeyResult<()> {
+        match status {
+            raw::Status::Ok => {
+                self.len += 1;
+                Ok(())
+            }
+            raw::Status::Err => Err(ValkeyError::Str("Failed writing the reply")),
+        }
+    }
+
+    /// Writes a single element.
+    pub fn push<T: Into<ValkeyValue>>(&mut self, value: T) -> ValkeyResult<()> {
+        match value.into() {
+            ValkeyValue::NoReply => Ok(()),
+            value => {
+                let status = self.ctx.reply(Ok(value));
+                self.written(status)
+            }
+        }
+    }
+
+    /// Writes a key and its value into a map or an attribute.
+    pub fn push_pair<K: Into<ValkeyValueKey>, V: Into<ValkeyValue>>(
+        &mut self,
+        key: K,
+        value: V,
+    ) -> ValkeyResult<()> {
+        let status = self.ctx.reply_with_key(key.into());
+        self.written(status)?;
+        self.push(value)
+    }
+
+    /// Writes a nested array, streaming its elements with the given callback.
+    pub fn array<F>(&mut self, f: F) -> ValkeyResult<()>
+    where
+        F: FnOnce(&mut ReplyWriter) -> ValkeyResult<()>,
+    {
+        self.nested(Aggregate::Array, f)
+    }
+
+    /// Writes a nested map, streaming its keys and values with the given callback.
+    pub fn map<F>(&mut self, f: F) -> ValkeyResult<()>
+    where
+        F: FnOnce(&mut ReplyWriter) -> ValkeyResult<()>,
+    {
+        self.nested(Aggregate::Map, f)
+    }
+
+    /// Writes a nested set, streaming its elements with the given callback.
+    pub fn set<F>(&mut self, f: F) -> ValkeyResult<()>
+    where
+        F: FnOnce(&mut ReplyWriter) -> ValkeyResult<()>,
+    {
+        self.nested(Aggregate::Set, f)
+    }
+
+    /// Returns the number of elements written so far. For maps and attributes,
+    /// keys and values are counted separately.
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn nested<F>(&mut self, aggregate: Aggregate, f: F) -> ValkeyResult<()>
+    where
+        F: FnOnce(&mut ReplyWriter) -> ValkeyResult<()>,
+    {
+        let status = aggregate.start(self.ctx);
+        self.written(status)?;
+        let mut writer = ReplyWriter::new(self.ctx, aggregate);
+        let res = f(&mut writer);
+        writer.finish();
+        res
+    }
+
+    /// Sets the length of the aggregate. A map with a missing value is completed with a null.
+    fn finish(mut self) {
+        if self.aggregate.is_map() && self.len % 2 != 0 {
+            let status = raw::reply_with_null(self.ctx.ctx);
+            let _ = self.written(status);
+        }
+        self.aggregate.finish(self.ctx, self.len);
+    }
+}
+
+impl Context {
+    fn reply_streaming<F>(&self, aggregate: Aggregate, f: F) -> ValkeyResult
+    where
+        F: FnOnce(&mut ReplyWriter) -> ValkeyResult<()>,
+    {
+        if aggregate.start(self) == raw::Status::Err {
+            // Attributes are rejected on RESP2, where they are dropped
+            if aggregate == Aggregate::Attribute {
+                return Ok(ValkeyValue::NoReply);
+            }
+            return Err(ValkeyError::Str("Failed writing the reply"));
+        }
+        let mut writer = ReplyWriter::new(self, aggregate);
+        if let Err(e) = f(&mut writer) {
+            if aggregate.is_map() && writer.len % 2 == 0 {
+                let _ = writer.push(ValkeyValue::Null);
+            }
+            let status = self.reply(Err(e));
+            let _ = writer.written(status);
+        }
+        writer.finish();
+        Ok(ValkeyValue::NoReply)
+    }
+
+    /// Replies with an array whose elements are written by the callback, as they
+    /// are produced, so they don't need to be collected in memory first. The array
+    /// length is set once the callback returns.
+    ///
+    /// The reply was already sent when this function returns, so it returns
+    /// [ValkeyValue::NoReply] to be returned from the command. If the callback
+    /// fails, its error is written as the last element of the array.
+    ///
+    /// ```rust,no_run,ignore
+    /// fn range(ctx: &Context, _args: Vec<ValkeyString>) -> ValkeyResult {
+    ///     ctx.reply_array_streaming(|w| {
+    ///         for i in 0..1_000_000i64 {
+    ///             w.push(i)?;
+    ///         }
+    ///         Ok(())
+    ///     })
+    /// }
+    /// ```
+    pub fn reply_array_streaming<F>(&self, f: F) -> ValkeyResult
+    where
+        F: FnOnce(&mut ReplyWriter) -> ValkeyResult<()>,
+    {
+        self.reply_streaming(Aggregate::Array, f)
+    }
+
+    /// Replies with a map whose keys and values are written by the callback.
+    /// On RESP2, the map is sent as a flat array of keys and values.
+    /// See [Self::reply_array_streaming] for details.
+    pub fn reply_map_streaming<F>(&self, f: F) -> ValkeyResult
+    where
+        F: FnOnce(&mut ReplyWriter) -> ValkeyResult<()>,
+    {
+        self.reply_streaming(Aggregate::Map, f)
+    }
+
+    /// Replies with a set whose elements are written by the callback.
+    /// On RESP2, the set is sent as an array.
+    /// See [Self::reply_array_streaming] for details.
+    pub fn reply_set_streaming<F>(&self, f: F) -> ValkeyResult
+    where
+        F: FnOnce(&mut ReplyWriter) -> ValkeyResult<()>,
+    {
+        self.reply_streaming(Aggregate::Set, f)
+    }
+
+    /// Writes an attribute whose keys and values are written by the callback. An
+    /// attribute is not a reply by itself, the reply it annotates must follow it.
+    /// Attributes are only supported on RESP3. On RESP2 they are dropped, as with
+    /// [ValkeyValue::WithAttributes]: nothing is written and the callback is not
+    /// called.
+    pub fn reply_attribute_streaming<F>(&self, f: F) -> ValkeyResult
+    where
+        F: FnOnce(&mut ReplyWriter) -> ValkeyResult<()>,
+    {
+        self.reply_streaming(Aggregate::Attribute, f)
+    }
+}
@@ -2,10 +2,15 @@ use std::borrow::Borrow;
 use std::cell::UnsafeCell;
 use std::ops::{Deref, DerefMut};
 use std::ptr;
+use std::thread;
+use std::time::{Duration, Instant};
 
-use crate::context::blocked::BlockedClient;
+use crate::context::blocked::{BlockedClient, MeasureGuard};
 use crate::{raw, Context, ValkeyResult};
 
+/// How long [ThreadSafeContext::lock_timeout] waits between two attempts.
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(1);
+
 pub struct ValkeyGILGuardScope<'ctx, 'mutex, T, G: ValkeyLockIndicator> {
     _context: &'ctx G,
     mutex: &'mutex ValkeyGILGuard<T>,
@@ -162,6 +167,12 @@ impl<T: Send> ThreadSafeContext<BlockedClient<T>> {
         let ctx = Context::new(self.ctx);
         ctx.reply(r)
     }
+
+    /// Starts measuring the time spent in the background for the blocked
+    /// client, see [BlockedClient::measure_time].
+    pub fn measure_time(&self) -> ValkeyResult<MeasureGuard<'_>> {
+        self.blocked_client.measure_time()
+    }
 }
 
 impl<B: Send> ThreadSafeContext<B> {
@@ -169,6 +180,47 @@ impl<B: Send> ThreadSafeContext<B> {
     /// similar to `std::sync::Mutex`.
     pub fn lock(&self) -> ContextGuard {
         unsafe { raw::RedisModule_ThreadSafeContextLock.unwrap()(self.ctx) };
+        Self::guard()
+    }
+
+    /// Locks this context if the GIL is free, without waiting for it, so a
+    /// background thread can skip its work while the main thread is busy.
+    pub fn try_lock(&self) -> Option<ContextGuard> {
+        let res = unsafe { raw::RedisModule_ThreadSafeContextTryLock.unwrap()(self.ctx) };
+        match raw::Status::from(res) {
+            raw::Status::Ok => Some(Self::guard()),
+            raw::Status::Err => None,
+        }
+    }
+
+    /// Locks this context, waiting at most `timeout` for the GIL to be free.
+    pub fn lock_timeout(&self, timeout: Duration) -> Option<ContextGuard> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(guard) = self.try_lock() {
+                return Some(guard);
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return None;
+            }
+            thread::sleep(remaining.min(LOCK_RETRY_INTERVAL));
+        }
+    }
+
+    /// Locks this context from a future of the [crate::executor], retrying
+    /// while the GIL is busy without blocking the executor thread, unlike
+    /// [Self::lock], so the other futures keep running meanwhile.
+    pub async fn lock_async(&self) -> ContextGuard {
+        loop {
+            if let Some(guard) = self.try_lock() {
+                return guard;
+            }
+            crate::executor::sleep(LOCK_RETRY_INTERVAL).await;
+        }
+    }
+
+    fn guard() -> ContextGuard {
         let ctx = unsafe { raw::RedisModule_GetThreadSafeContext.unwrap()(ptr::null_mut()) };
         let ctx = Context::new(ctx);
         ContextGuard { ctx }
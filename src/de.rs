@@ -0,0 +1,764 @@
+use std::fmt::Display;
+use std::iter::Peekable;
+use std::vec;
+
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::{forward_to_deserialize_any, Deserializer as _};
+
+use crate::{CallReply, CallResult, CodedError, ErrorReply, SerdeError, ValkeyError};
+
+/// Converts a [CallReply] into any [DeserializeOwned] type, instead of matching
+/// the reply variants by hand.
+///
+/// The conversion follows the requested type rather than the reply type, so the
+/// same type can be read from RESP2 and RESP3 replies:
+/// * maps and structs are read from RESP3 maps or from RESP2 flat arrays of keys and values,
+/// * sequences of tuples are read from arrays of arrays, from flat arrays or from maps,
+/// * numbers and booleans are also parsed out of string replies,
+/// * `Option` is `None` for null replies.
+///
+/// Error replies nested in the reply fail the conversion with their error code.
+///
+/// ```rust,no_run,ignore
+/// let reply: CallResult = ctx.call_ext("ZRANGE", &CallOptionsBuilder::new().resp(CallOptionResp::Auto).build(), &["z", "0", "-1", "WITHSCORES"]);
+/// let scores: Vec<(String, f64)> = valkey_module::from_call_result(&reply)?;
+/// ```
+pub fn from_call_reply<T: DeserializeOwned>(reply: &CallReply) -> Result<T, ValkeyError> {
+    T::deserialize(Deserializer::new(reply)).map_err(|e| ValkeyError::Coded(e.0))
+}
+
+/// Same as [from_call_reply], but takes the [CallResult] returned by a call, an
+/// error reply being returned as a [ValkeyError::Coded] error.
+pub fn from_call_result<T: DeserializeOwned>(result: &CallResult) -> Result<T, ValkeyError> {
+    match result {
+        Ok(reply) => from_call_reply(reply),
+        Err(e) => Err(ValkeyError::Coded(coded_error(e))),
+    }
+}
+
+impl de::Error for SerdeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        SerdeError(CodedError::new("ERR", msg.to_string()))
+    }
+}
+
+/// Splits an error reply into its error code and message.
+fn coded_error(e: &ErrorReply) -> CodedError {
+    let msg = e
+        .to_utf8_string()
+        .unwrap_or_else(|| "can not convert error into String".to_owned());
+    match msg.split_once(' ') {
+        Some((code, message)) => CodedError::new(code.to_owned(), message),
+        None => CodedError::new("ERR", msg),
+    }
+}
+
+fn reply_of<'a, 'root>(result: &'a CallResult<'root>) -> Result<&'a CallReply<'root>, SerdeError> {
+    result.as_ref().map_err(|e| SerdeError(coded_error(e)))
+}
+
+/// A [serde::Deserializer] reading a [CallReply], see [from_call_reply].
+pub struct Deserializer<'a, 'root> {
+    reply: &'a CallReply<'root>,
+}
+
+impl<'a, 'root> Deserializer<'a, 'root> {
+    pub fn new(reply: &'a CallReply<'root>) -> Self {
+        Self { reply }
+    }
+
+    /// Returns the reply as text, for string like and scalar replies.
+    fn text(&self) -> Result<String, SerdeError> {
+        let text = match self.reply {
+            CallReply::String(r) => r.to_string(),
+            CallReply::VerbatimString(r) => r
+                .as_parts()
+                .map(|(_, text)| text)
+                .and_then(|text| std::str::from_utf8(text).ok().map(str::to_owned)),
+            CallReply::BigNumber(r) => r.to_string(),
+            CallReply::I64(r) => Some(r.to_i64().to_string()),
+            CallReply::Double(r) => Some(r.to_double().to_string()),
+            CallReply::Bool(r) => Some(r.to_bool().to_string()),
+            _ => None,
+        };
+        text.ok_or_else(|| {
+            de::Error::custom(format!("expected a string reply, got {}", self.kind()))
+        })
+    }
+
+    fn kind(&self) -> &'static str {
+        match self.reply {
+            CallReply::Unknown => "an unknown reply",
+            CallReply::I64(_) => "an integer reply",
+            CallReply::String(_) => "a string reply",
+            CallReply::Array(_) => "an array reply",
+            CallReply::Null(_) => "a null reply",
+            CallReply::Map(_) => "a map reply",
+            CallReply::Set(_) => "a set reply",
+            CallReply::Bool(_) => "a boolean reply",
+            CallReply::Double(_) => "a double reply",
+            CallReply::BigNumber(_) => "a big number reply",
+            CallReply::VerbatimString(_) => "a verbatim string reply",
+        }
+    }
+
+    /// Numbers are also parsed out of string replies, as returned by RESP2.
+    fn deserialize_number<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self.reply {
+            CallReply::String(_) | CallReply::VerbatimString(_) | CallReply::BigNumber(_) => {
+                let text = self.text()?;
+                if let Ok(v) = text.parse::<i64>() {
+                    visitor.visit_i64(v)
+                } else if let Ok(v) = text.parse::<u64>() {
+                    visitor.visit_u64(v)
+                } else if let Ok(v) = text.parse::<i128>() {
+                    visitor.visit_i128(v)
+                } else if let Ok(v) = text.parse::<u128>() {
+                    visitor.visit_u128(v)
+                } else if let Ok(v) = text.parse::<f64>() {
+                    visitor.visit_f64(v)
+                } else {
+                    visitor.visit_string(text)
+                }
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+}
+
+impl<'de, 'a, 'root> de::Deserializer<'de> for Deserializer<'a, 'root> {
+    type Error = SerdeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self.reply {
+            CallReply::Unknown => Err(de::Error::custom("can not convert an unknown reply")),
+            CallReply::I64(r) => visitor.visit_i64(r.to_i64()),
+            CallReply::String(r) => match std::str::from_utf8(r.as_bytes()) {
+                Ok(s) => visitor.visit_str(s),
+                Err(_) => visitor.visit_bytes(r.as_bytes()),
+            },
+            CallReply::Array(_) | CallReply::Set(_) => self.deserialize_seq(visitor),
+            CallReply::Null(_) => visitor.visit_unit(),
+            CallReply::Map(_) => self.deserialize_map(visitor),
+            CallReply::Bool(r) => visitor.visit_bool(r.to_bool()),
+            CallReply::Double(r) => visitor.visit_f64(r.to_double()),
+            CallReply::BigNumber(_) => self.deserialize_number(visitor),
+            CallReply::VerbatimString(_) => visitor.visit_string(self.text()?),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self.reply {
+            CallReply::I64(r) if r.to_i64() == 0 => visitor.visit_bool(false),
+            CallReply::I64(r) if r.to_i64() == 1 => visitor.visit_bool(true),
+            CallReply::String(r) if r.as_bytes() == b"0" => visitor.visit_bool(false),
+            CallReply::String(r) if r.as_bytes() == b"1" => visitor.visit_bool(true),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_number(visitor)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_number(visitor)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_number(visitor)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_number(visitor)
+    }
+
+    fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_number(visitor)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_number(visitor)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_number(visitor)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_number(visitor)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_number(visitor)
+    }
+
+    fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_number(visitor)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_number(visitor)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_number(visitor)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self.reply {
+            CallReply::String(_) => self.deserialize_any(visitor),
+            _ => visitor.visit_string(self.text()?),
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self.reply {
+            CallReply::String(r) => visitor.visit_bytes(r.as_bytes()),
+            CallReply::Array(_) | CallReply::Set(_) => self.deserialize_seq(visitor),
+            _ => visitor.visit_byte_buf(self.text()?.into_bytes()),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self.reply {
+            CallReply::Null(_) => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        // Replies such as `OK` carry no value.
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self.reply {
+            CallReply::Array(r) => visitor.visit_seq(ReplySeq::new(r.iter().collect())),
+            CallReply::Set(r) => visitor.visit_seq(ReplySeq::new(r.iter().collect())),
+            CallReply::Map(r) => visitor.visit_seq(ReplyMap::new(r.iter().collect())),
+            CallReply::Null(_) => visitor.visit_seq(ReplySeq::new(Vec::new())),
+            _ => Err(de::Error::custom(format!(
+                "expected an array reply, got {}",
+                self.kind()
+            ))),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self.reply {
+            CallReply::Map(r) => visitor.visit_map(ReplyMap::new(r.iter().collect())),
+            // RESP2 replies maps as flat arrays of keys and values
+            CallReply::Array(r) => visitor.visit_map(ReplySeq::new(r.iter().collect())),
+            CallReply::Null(_) => visitor.visit_map(ReplyMap::new(Vec::new())),
+            _ => Err(de::Error::custom(format!(
+                "expected a map reply, got {}",
+                self.kind()
+            ))),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        // A variant holding data is a single entry map, keyed by the variant name.
+        let entry = match self.reply {
+            CallReply::Map(r) if r.len() == 1 => r.iter().next(),
+            CallReply::Array(r) if r.len() == 2 => {
+                let mut items = r.iter();
+                items.next().zip(items.next())
+            }
+            _ => None,
+        };
+        match entry {
+            Some((variant, value)) => visitor.visit_enum(ReplyEnum { variant, value }),
+            None => visitor.visit_enum(self.text()?.into_deserializer()),
+        }
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_unit()
+    }
+}
+
+/// Reads the elements of an array or a set, or the keys and values of a RESP2
+/// flat array as a map.
+struct ReplySeq<'r> {
+    items: Peekable<vec::IntoIter<CallResult<'r>>>,
+}
+
+impl<'r> ReplySeq<'r> {
+    fn new(items: Vec<CallResult<'r>>) -> Self {
+        Self {
+            items: items.into_iter().peekable(),
+        }
+    }
+
+    fn next_item(&mut self) -> Result<CallResult<'r>, SerdeError> {
+        self.items
+            .next()
+            .ok_or_else(|| de::Error::custom("missing element in array reply"))
+    }
+}
+
+impl<'de, 'r> de::SeqAccess<'de> for ReplySeq<'r> {
+    type Error = SerdeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, SerdeError> {
+        if self.items.peek().is_none() {
+            return Ok(None);
+        }
+        seed.deserialize(Element { seq: self }).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+impl<'de, 'r> de::MapAccess<'de> for ReplySeq<'r> {
+    type Error = SerdeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, SerdeError> {
+        match self.items.next() {
+            Some(key) => seed
+                .deserialize(Deserializer::new(reply_of(&key)?))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, SerdeError> {
+        let value = self.next_item()?;
+        seed.deserialize(Deserializer::new(reply_of(&value)?))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len() / 2)
+    }
+}
+
+/// An element of a [ReplySeq]. A tuple read out of an element which is not an
+/// aggregate is made of this element and the ones following it, which is how
+/// RESP2 flattens pairs such as `ZRANGE ... WITHSCORES`.
+struct Element<'s, 'r> {
+    seq: &'s mut ReplySeq<'r>,
+}
+
+macro_rules! forward_to_element {
+    ($($method:ident($($arg:ident: $ty:ty),*)),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, SerdeError> {
+                let item = self.seq.next_item()?;
+                Deserializer::new(reply_of(&item)?).$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 's, 'r> de::Deserializer<'de> for Element<'s, 'r> {
+    type Error = SerdeError;
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        match self.seq.items.peek() {
+            Some(Ok(CallReply::Array(_) | CallReply::Set(_) | CallReply::Map(_))) => {
+                let item = self.seq.next_item()?;
+                Deserializer::new(reply_of(&item)?).deserialize_tuple(len, visitor)
+            }
+            _ => visitor.visit_seq(Group {
+                seq: self.seq,
+                remaining: len,
+            }),
+        }
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    forward_to_element! {
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_option(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+        deserialize_ignored_any(),
+    }
+}
+
+/// A tuple made of consecutive elements of a flat array.
+struct Group<'s, 'r> {
+    seq: &'s mut ReplySeq<'r>,
+    remaining: usize,
+}
+
+impl<'de, 's, 'r> de::SeqAccess<'de> for Group<'s, 'r> {
+    type Error = SerdeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, SerdeError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        let Some(item) = self.seq.items.next() else {
+            return Ok(None);
+        };
+        self.remaining -= 1;
+        seed.deserialize(Deserializer::new(reply_of(&item)?))
+            .map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+/// Reads the entries of a map reply, as a map or as a sequence of pairs.
+struct ReplyMap<'r> {
+    entries: vec::IntoIter<(CallResult<'r>, CallResult<'r>)>,
+    value: Option<CallResult<'r>>,
+}
+
+impl<'r> ReplyMap<'r> {
+    fn new(entries: Vec<(CallResult<'r>, CallResult<'r>)>) -> Self {
+        Self {
+            entries: entries.into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de, 'r> de::MapAccess<'de> for ReplyMap<'r> {
+    type Error = SerdeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, SerdeError> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(Deserializer::new(reply_of(&key)?))
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, SerdeError> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| de::Error::custom("map value read before its key"))?;
+        seed.deserialize(Deserializer::new(reply_of(&value)?))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+impl<'de, 'r> de::SeqAccess<'de> for ReplyMap<'r> {
+    type Error = SerdeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, SerdeError> {
+        match self.entries.next() {
+            Some((key, value)) => seed.deserialize(Pair(vec![key, value])).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+/// A key and its value, read as a sequence of two elements.
+struct Pair<'r>(Vec<CallResult<'r>>);
+
+impl<'de, 'r> de::Deserializer<'de> for Pair<'r> {
+    type Error = SerdeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_seq(ReplySeq::new(self.0))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// An enum variant read out of a single entry map, or of a RESP2 pair.
+struct ReplyEnum<'r> {
+    variant: CallResult<'r>,
+    value: CallResult<'r>,
+}
+
+impl<'de, 'r> de::EnumAccess<'de> for ReplyEnum<'r> {
+    type Error = SerdeError;
+    type Variant = ReplyVariant<'r>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, ReplyVariant<'r>), SerdeError> {
+        let variant = seed.deserialize(Deserializer::new(reply_of(&self.variant)?))?;
+        Ok((variant, ReplyVariant { value: self.value }))
+    }
+}
+
+struct ReplyVariant<'r> {
+    value: CallResult<'r>,
+}
+
+impl<'de, 'r> de::VariantAccess<'de> for ReplyVariant<'r> {
+    type Error = SerdeError;
+
+    fn unit_variant(self) -> Result<(), SerdeError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, SerdeError> {
+        seed.deserialize(Deserializer::new(reply_of(&self.value)?))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        de::Deserializer::deserialize_tuple(Deserializer::new(reply_of(&self.value)?), len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        de::Deserializer::deserialize_map(Deserializer::new(reply_of(&self.value)?), visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redisvalue::ValkeyValueKey;
+    use crate::{Context, ValkeyValue};
+    use serde::Deserialize;
+    use std::collections::{BTreeMap, HashMap};
+
+    fn bulk(s: &str) -> ValkeyValue {
+        ValkeyValue::BulkString(s.to_owned())
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Item {
+        name: String,
+        #[serde(rename = "qty")]
+        quantity: u32,
+        tag: Option<String>,
+    }
+
+    #[test]
+    fn reads_pairs_from_flat_and_nested_arrays() {
+        let mut context = Context::test();
+        context.expect_call(
+            "ZRANGE",
+            &["z", "0", "-1", "WITHSCORES"],
+            ValkeyValue::Array(vec![bulk("a"), bulk("1.5"), bulk("b"), bulk("2")]),
+        );
+        context.expect_call(
+            "ZRANGE",
+            &["y", "0", "-1", "WITHSCORES"],
+            ValkeyValue::Array(vec![
+                ValkeyValue::Array(vec![bulk("a"), ValkeyValue::Float(1.5)]),
+                ValkeyValue::Array(vec![bulk("b"), ValkeyValue::Float(2.0)]),
+            ]),
+        );
+        let expected = vec![("a".to_owned(), 1.5), ("b".to_owned(), 2.0)];
+
+        let flat: Vec<(String, f64)> = context
+            .call_typed("ZRANGE", &["z", "0", "-1", "WITHSCORES"])
+            .unwrap();
+        let nested: Vec<(String, f64)> = context
+            .call_typed("ZRANGE", &["y", "0", "-1", "WITHSCORES"])
+            .unwrap();
+
+        assert_eq!(flat, expected);
+        assert_eq!(nested, expected);
+    }
+
+    #[test]
+    fn reads_structs_from_flat_arrays_and_maps() {
+        let mut context = Context::test();
+        context.expect_call(
+            "HGETALL",
+            &["a"],
+            ValkeyValue::Array(vec![bulk("name"), bulk("box"), bulk("qty"), bulk("3")]),
+        );
+        context.expect_call(
+            "HGETALL",
+            &["b"],
+            ValkeyValue::OrderedMap(BTreeMap::from([
+                (ValkeyValueKey::String("name".to_owned()), bulk("box")),
+                (
+                    ValkeyValueKey::String("qty".to_owned()),
+                    ValkeyValue::Integer(3),
+                ),
+                (ValkeyValueKey::String("tag".to_owned()), bulk("new")),
+            ])),
+        );
+
+        let flat: Item = context.call_typed("HGETALL", &["a"]).unwrap();
+        let map: HashMap<String, String> = context.call_typed("HGETALL", &["b"]).unwrap();
+
+        assert_eq!(
+            flat,
+            Item {
+                name: "box".to_owned(),
+                quantity: 3,
+                tag: None,
+            }
+        );
+        assert_eq!(map["qty"], "3");
+        assert_eq!(map["tag"], "new");
+    }
+
+    #[test]
+    fn returns_error_replies_with_their_code() {
+        let mut context = Context::test();
+        context.expect_call(
+            "GET",
+            &["k"],
+            ValkeyValue::StaticError("WRONGTYPE Operation against a key"),
+        );
+        context.expect_call(
+            "MGET",
+            &["k"],
+            ValkeyValue::Array(vec![bulk("not a number")]),
+        );
+
+        let err = context.call_typed::<String, _>("GET", &["k"]).unwrap_err();
+        assert_eq!(err.code(), "WRONGTYPE");
+
+        let err = context
+            .call_typed::<Vec<i64>, _>("MGET", &["k"])
+            .unwrap_err();
+        assert_eq!(err.code(), "ERR");
+    }
+}
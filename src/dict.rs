@@ -0,0 +1,391 @@
+use std::ffi::CStr;
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+use std::os::raw::c_void;
+use std::ptr::{self, NonNull};
+
+use crate::{raw, ValkeyError, ValkeyResult, ValkeyString};
+
+/// The operators accepted by [ModuleDict::seek] and [DictIter::reseek].
+const SEEK_OPS: [&str; 7] = ["^", "$", ">", ">=", "<", "<=", "=="];
+
+fn seek_op(op: &str) -> ValkeyResult<&'static CStr> {
+    let op = match op {
+        "^" => c_str(b"^\0"),
+        "$" => c_str(b"$\0"),
+        ">" => c_str(b">\0"),
+        ">=" => c_str(b">=\0"),
+        "<" => c_str(b"<\0"),
+        "<=" => c_str(b"<=\0"),
+        "==" => c_str(b"==\0"),
+        _ => {
+            return Err(ValkeyError::String(format!(
+                "ERR invalid seek operator '{op}', expected one of {SEEK_OPS:?}"
+            )))
+        }
+    };
+    Ok(op)
+}
+
+fn c_str(bytes: &'static [u8]) -> &'static CStr {
+    CStr::from_bytes_with_nul(bytes).unwrap()
+}
+
+/// A key of a [ModuleDict], a binary string compared lexicographically.
+pub trait DictKey {
+    /// Returns the bytes of the key.
+    fn as_key(&self) -> &[u8];
+}
+
+impl DictKey for [u8] {
+    fn as_key(&self) -> &[u8] {
+        self
+    }
+}
+
+impl<const N: usize> DictKey for [u8; N] {
+    fn as_key(&self) -> &[u8] {
+        self
+    }
+}
+
+impl DictKey for Vec<u8> {
+    fn as_key(&self) -> &[u8] {
+        self
+    }
+}
+
+impl DictKey for str {
+    fn as_key(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl DictKey for String {
+    fn as_key(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl DictKey for ValkeyString {
+    fn as_key(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<K: DictKey + ?Sized> DictKey for &K {
+    fn as_key(&self) -> &[u8] {
+        (**self).as_key()
+    }
+}
+
+/// An ordered map, backed by the server's radix tree, whose memory is
+/// accounted in the server memory. Keys are binary strings, ordered
+/// lexicographically, such as `&[u8]`, `&str` or `&ValkeyString`, see [DictKey].
+///
+/// ```rust,no_run,ignore
+/// let mut dict = ModuleDict::new();
+/// dict.insert("user:2", 2);
+/// dict.insert("user:1", 1);
+/// for (key, value) in dict.seek(">=", "user:")? {
+///     ...
+/// }
+/// ```
+pub struct ModuleDict<V> {
+    dict: NonNull<raw::RedisModuleDict>,
+    _marker: PhantomData<Box<V>>,
+}
+
+// The dictionary only holds the values, which are owned by it, and allocates
+// with the server allocator, which can be used from any thread.
+unsafe impl<V: Send> Send for ModuleDict<V> {}
+unsafe impl<V: Sync> Sync for ModuleDict<V> {}
+
+impl<V> ModuleDict<V> {
+    pub fn new() -> ModuleDict<V> {
+        let dict = unsafe { raw::RedisModule_CreateDict.unwrap()(ptr::null_mut()) };
+        ModuleDict {
+            dict: NonNull::new(dict).expect("RedisModule_CreateDict returned NULL"),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        unsafe { raw::RedisModule_DictSize.unwrap()(self.dict.as_ptr()) as usize }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get_ptr(&self, key: &[u8]) -> Option<NonNull<V>> {
+        let mut nokey = 0;
+        let value = unsafe {
+            raw::RedisModule_DictGetC.unwrap()(
+                self.dict.as_ptr(),
+                key.as_ptr() as *mut c_void,
+                key.len(),
+                &mut nokey,
+            )
+        };
+        if nokey != 0 {
+            return None;
+        }
+        NonNull::new(value.cast::<V>())
+    }
+
+    /// Returns the value of the given key.
+    pub fn get<K: DictKey>(&self, key: K) -> Option<&V> {
+        self.get_ptr(key.as_key())
+            .map(|value| unsafe { &*value.as_ptr() })
+    }
+
+    /// Returns a mutable reference to the value of the given key.
+    pub fn get_mut<K: DictKey>(&mut self, key: K) -> Option<&mut V> {
+        self.get_ptr(key.as_key())
+            .map(|value| unsafe { &mut *value.as_ptr() })
+    }
+
+    pub fn contains_key<K: DictKey>(&self, key: K) -> bool {
+        self.get_ptr(key.as_key()).is_some()
+    }
+
+    /// Sets the value of the given key, returning the value it replaced, if any.
+    pub fn insert<K: DictKey>(&mut self, key: K, value: V) -> Option<V> {
+        let key = key.as_key();
+        let old = self.get_ptr(key);
+        let value = Box::into_raw(Box::new(value));
+        unsafe {
+            raw::RedisModule_DictReplaceC.unwrap()(
+                self.dict.as_ptr(),
+                key.as_ptr() as *mut c_void,
+                key.len(),
+                value.cast::<c_void>(),
+            )
+        };
+        old.map(|old| *unsafe { Box::from_raw(old.as_ptr()) })
+    }
+
+    /// Removes the given key, returning its value, if any.
+    pub fn remove<K: DictKey>(&mut self, key: K) -> Option<V> {
+        let key = key.as_key();
+        let mut old: *mut c_void = ptr::null_mut();
+        let res: raw::Status = unsafe {
+            raw::RedisModule_DictDelC.unwrap()(
+                self.dict.as_ptr(),
+                key.as_ptr() as *mut c_void,
+                key.len(),
+                (&mut old as *mut *mut c_void).cast::<c_void>(),
+            )
+        }
+        .into();
+        match res {
+            raw::Status::Ok => Some(*unsafe { Box::from_raw(old.cast::<V>()) }),
+            raw::Status::Err => None,
+        }
+    }
+
+    /// Removes all the entries.
+    pub fn clear(&mut self) {
+        self.free_values();
+        unsafe { raw::RedisModule_FreeDict.unwrap()(ptr::null_mut(), self.dict.as_ptr()) };
+        let dict = unsafe { raw::RedisModule_CreateDict.unwrap()(ptr::null_mut()) };
+        self.dict = NonNull::new(dict).expect("RedisModule_CreateDict returned NULL");
+    }
+
+    /// Iterates over all the entries, in the order of their keys.
+    pub fn iter(&self) -> DictIter<'_, V> {
+        self.start(c_str(b"^\0"), &[])
+    }
+
+    /// Iterates over the entries starting at the first key matching the given
+    /// operator and key, moving forward with [Iterator::next] or backward with
+    /// [DictIter::prev]. The operator is one of:
+    ///
+    /// * `^` - The first key, the given key is ignored.
+    /// * `$` - The last key, the given key is ignored.
+    /// * `>`, `>=`, `<`, `<=` - The first key greater, greater or equal, lower
+    ///   or lower or equal to the given key.
+    /// * `==` - The given key.
+    pub fn seek<K: DictKey>(&self, op: &str, key: K) -> ValkeyResult<DictIter<'_, V>> {
+        Ok(self.start(seek_op(op)?, key.as_key()))
+    }
+
+    /// Iterates over the entries whose keys are within the given range of
+    /// key references.
+    ///
+    /// ```rust,no_run,ignore
+    /// let users: Vec<_> = dict.range("user:".."user;").collect();
+    /// ```
+    pub fn range<'k, K, R>(&self, range: R) -> DictIter<'_, V>
+    where
+        K: DictKey + ?Sized + 'k,
+        R: RangeBounds<&'k K>,
+    {
+        let mut iter = match range.start_bound() {
+            Bound::Included(key) => self.start(c_str(b">=\0"), key.as_key()),
+            Bound::Excluded(key) => self.start(c_str(b">\0"), key.as_key()),
+            Bound::Unbounded => self.iter(),
+        };
+        iter.end = match range.end_bound() {
+            Bound::Included(key) => Some((c_str(b"<=\0"), key.as_key().to_vec())),
+            Bound::Excluded(key) => Some((c_str(b"<\0"), key.as_key().to_vec())),
+            Bound::Unbounded => None,
+        };
+        iter
+    }
+
+    /// Returns the memory used by the dictionary itself, not including the
+    /// memory of its values, to be reported by the `mem_usage` callback of a
+    /// data type.
+    pub fn malloc_size(&self) -> usize {
+        unsafe { raw::RedisModule_MallocSizeDict.unwrap()(self.dict.as_ptr()) }
+    }
+
+    fn start(&self, op: &CStr, key: &[u8]) -> DictIter<'_, V> {
+        let iter = unsafe {
+            raw::RedisModule_DictIteratorStartC.unwrap()(
+                self.dict.as_ptr(),
+                op.as_ptr(),
+                key.as_ptr() as *mut c_void,
+                key.len(),
+            )
+        };
+        DictIter {
+            iter: NonNull::new(iter).expect("RedisModule_DictIteratorStartC returned NULL"),
+            end: None,
+            _marker: PhantomData,
+        }
+    }
+
+    fn free_values(&mut self) {
+        let mut iter = self.start(c_str(b"^\0"), &[]);
+        while let Some((_, value)) = iter.next_ptr(unsafe { raw::RedisModule_DictNextC.unwrap() }) {
+            drop(unsafe { Box::from_raw(value) });
+        }
+    }
+}
+
+impl<V> Default for ModuleDict<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> Drop for ModuleDict<V> {
+    fn drop(&mut self) {
+        self.free_values();
+        unsafe { raw::RedisModule_FreeDict.unwrap()(ptr::null_mut(), self.dict.as_ptr()) };
+    }
+}
+
+impl<'a, V> IntoIterator for &'a ModuleDict<V> {
+    type Item = (Vec<u8>, &'a V);
+    type IntoIter = DictIter<'a, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+type DictStep = unsafe extern "C" fn(
+    di: *mut raw::RedisModuleDictIter,
+    keylen: *mut usize,
+    dataptr: *mut *mut c_void,
+) -> *mut c_void;
+
+/// An iterator over the entries of a [ModuleDict], see [ModuleDict::seek].
+/// Yields the keys and references to their values.
+pub struct DictIter<'a, V> {
+    iter: NonNull<raw::RedisModuleDictIter>,
+    end: Option<(&'static CStr, Vec<u8>)>,
+    _marker: PhantomData<&'a ModuleDict<V>>,
+}
+
+impl<'a, V> DictIter<'a, V> {
+    fn next_ptr(&mut self, step: DictStep) -> Option<(Vec<u8>, *mut V)> {
+        let mut keylen = 0;
+        let mut value: *mut c_void = ptr::null_mut();
+        let key = unsafe { step(self.iter.as_ptr(), &mut keylen, &mut value) };
+        if key.is_null() {
+            return None;
+        }
+        let key = unsafe { std::slice::from_raw_parts(key.cast::<u8>(), keylen) }.to_vec();
+        Some((key, value.cast::<V>()))
+    }
+
+    fn step(&mut self, step: DictStep) -> Option<(Vec<u8>, &'a V)> {
+        let (key, value) = self.next_ptr(step)?;
+        if let Some((op, end)) = &self.end {
+            if !self.compare_c(op, end) {
+                return None;
+            }
+        }
+        Some((key, unsafe { &*value }))
+    }
+
+    /// Returns the previous entry, moving the iterator backward.
+    pub fn prev(&mut self) -> Option<(Vec<u8>, &'a V)> {
+        self.step(unsafe { raw::RedisModule_DictPrevC.unwrap() })
+    }
+
+    /// Moves the iterator to the first key matching the given operator and
+    /// key, see [ModuleDict::seek].
+    pub fn reseek<K: DictKey>(&mut self, op: &str, key: K) -> ValkeyResult<()> {
+        let op = seek_op(op)?;
+        let key = key.as_key();
+        let res: raw::Status = unsafe {
+            raw::RedisModule_DictIteratorReseekC.unwrap()(
+                self.iter.as_ptr(),
+                op.as_ptr(),
+                key.as_ptr() as *mut c_void,
+                key.len(),
+            )
+        }
+        .into();
+        match res {
+            raw::Status::Ok => Ok(()),
+            raw::Status::Err => Err(ValkeyError::Str("ERR failed seeking the iterator")),
+        }
+    }
+
+    /// Compares the key of the entry last returned by the iterator with the
+    /// given key, using one of the `>`, `>=`, `<`, `<=` or `==` operators.
+    /// Returns false if the iterator did not return any entry yet.
+    pub fn compare<K: DictKey>(&self, op: &str, key: K) -> ValkeyResult<bool> {
+        match op {
+            "^" | "$" => Err(ValkeyError::String(format!(
+                "ERR invalid compare operator '{op}'"
+            ))),
+            op => Ok(self.compare_c(seek_op(op)?, key.as_key())),
+        }
+    }
+
+    fn compare_c(&self, op: &CStr, key: &[u8]) -> bool {
+        let res: raw::Status = unsafe {
+            raw::RedisModule_DictCompareC.unwrap()(
+                self.iter.as_ptr(),
+                op.as_ptr(),
+                key.as_ptr() as *mut c_void,
+                key.len(),
+            )
+        }
+        .into();
+        res == raw::Status::Ok
+    }
+}
+
+impl<'a, V> Iterator for DictIter<'a, V> {
+    type Item = (Vec<u8>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.step(unsafe { raw::RedisModule_DictNextC.unwrap() })
+    }
+}
+
+impl<'a, V> Drop for DictIter<'a, V> {
+    fn drop(&mut self) {
+        unsafe { raw::RedisModule_DictIteratorStop.unwrap()(self.iter.as_ptr()) };
+    }
+}
@@ -0,0 +1,181 @@
+//! A minimal executor, running the futures of the async commands registered
+//! with the `#[command]` macro on a dedicated thread.
+//!
+//! The futures run without the GIL, which they take with
+//! [crate::ThreadSafeContext::lock_async] when needed. As they all share the
+//! same thread, a future blocking it, waiting for the GIL with
+//! [crate::ThreadSafeContext::lock] or doing blocking I/O, stalls all the others.
+//!
+//! The executor has no reactor: the only timer is [sleep], and futures relying
+//! on the reactor of another runtime, such as the timers or sockets of tokio,
+//! panic or never complete.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::task::{self, Poll, Wake, Waker};
+use std::thread;
+use std::time::{Duration, Instant};
+
+type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+struct Task {
+    future: Mutex<Option<BoxFuture>>,
+}
+
+impl Task {
+    fn poll(self: Arc<Self>) {
+        let mut future = self.future.lock().unwrap();
+        let Some(mut fut) = future.take() else {
+            // Already completed, woken up once more
+            return;
+        };
+        let waker = Waker::from(Arc::clone(&self));
+        let mut cx = task::Context::from_waker(&waker);
+        // A panicking future is dropped, the executor keeps running the others
+        if let Ok(Poll::Pending) =
+            panic::catch_unwind(AssertUnwindSafe(|| fut.as_mut().poll(&mut cx)))
+        {
+            *future = Some(fut);
+        }
+    }
+}
+
+impl Wake for Task {
+    fn wake(self: Arc<Self>) {
+        // The executor thread only stops with the process
+        let _ = queue().lock().unwrap().send(self);
+    }
+}
+
+fn queue() -> &'static Mutex<Sender<Arc<Task>>> {
+    static QUEUE: OnceLock<Mutex<Sender<Arc<Task>>>> = OnceLock::new();
+    QUEUE.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Arc<Task>>();
+        thread::Builder::new()
+            .name("valkey-module-executor".to_owned())
+            .spawn(move || receiver.into_iter().for_each(Task::poll))
+            .expect("failed to spawn the executor thread");
+        Mutex::new(sender)
+    })
+}
+
+/// Runs the future to completion on the executor thread.
+pub fn spawn<F: Future<Output = ()> + Send + 'static>(future: F) {
+    Arc::new(Task {
+        future: Mutex::new(Some(Box::pin(future))),
+    })
+    .wake();
+}
+
+/// A future returning the panic of the wrapped future as an error, see
+/// [catch_unwind].
+pub struct CatchUnwind<F> {
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for CatchUnwind<F> {
+    type Output = thread::Result<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        match panic::catch_unwind(AssertUnwindSafe(|| self.future.as_mut().poll(cx))) {
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(panic) => Poll::Ready(Err(panic)),
+        }
+    }
+}
+
+/// Runs the future, returning an error if it panics, so the future awaiting it
+/// can still complete, e.g. by replying to its blocked client.
+pub fn catch_unwind<F: Future>(future: F) -> CatchUnwind<F> {
+    CatchUnwind {
+        future: Box::pin(future),
+    }
+}
+
+struct Timer {
+    deadline: Instant,
+    waker: Waker,
+}
+
+impl PartialEq for Timer {
+    fn eq(&self, other: &Self) -> bool {
+        self.deadline == other.deadline
+    }
+}
+
+impl Eq for Timer {}
+
+impl PartialOrd for Timer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timer {
+    // Reversed, so the heap pops the earliest deadline first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.deadline.cmp(&self.deadline)
+    }
+}
+
+fn timers() -> &'static (Mutex<BinaryHeap<Timer>>, Condvar) {
+    static TIMERS: OnceLock<(Mutex<BinaryHeap<Timer>>, Condvar)> = OnceLock::new();
+    TIMERS.get_or_init(|| {
+        thread::Builder::new()
+            .name("valkey-module-timer".to_owned())
+            .spawn(run_timers)
+            .expect("failed to spawn the timer thread");
+        (Mutex::new(BinaryHeap::new()), Condvar::new())
+    })
+}
+
+fn run_timers() {
+    let (timers, condvar) = timers();
+    let mut timers = timers.lock().unwrap();
+    loop {
+        let now = Instant::now();
+        while timers.peek().is_some_and(|timer| timer.deadline <= now) {
+            timers.pop().unwrap().waker.wake();
+        }
+        timers = match timers.peek().map(|timer| timer.deadline - now) {
+            Some(timeout) => condvar.wait_timeout(timers, timeout).unwrap().0,
+            None => condvar.wait(timers).unwrap(),
+        };
+    }
+}
+
+/// A future completing once its deadline is reached, see [sleep].
+pub struct Sleep {
+    deadline: Instant,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<()> {
+        if Instant::now() >= self.deadline {
+            return Poll::Ready(());
+        }
+        let (timers, condvar) = timers();
+        timers.lock().unwrap().push(Timer {
+            deadline: self.deadline,
+            waker: cx.waker().clone(),
+        });
+        condvar.notify_one();
+        Poll::Pending
+    }
+}
+
+/// Waits for the given duration without blocking the executor thread, so the
+/// other futures keep running meanwhile.
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep {
+        deadline: Instant::now() + duration,
+    }
+}
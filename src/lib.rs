@@ -7,16 +7,20 @@ extern crate num_traits;
 
 pub mod alloc;
 pub mod apierror;
+pub mod args;
 pub mod defrag;
 pub mod digest;
 pub mod error;
+pub mod executor;
 pub mod native_types;
 pub mod raw;
 pub mod rediserror;
 mod redismodule;
 pub mod redisraw;
 pub mod redisvalue;
+pub mod ser;
 pub mod stream;
+pub mod workers;
 
 #[cfg(any(test, feature = "test-shims"))]
 #[path = "test-shims/mod.rs"]
@@ -24,16 +28,31 @@ pub mod test_shims;
 
 pub mod configuration;
 mod context;
+pub mod de;
+pub mod dict;
 pub mod key;
 pub mod logging;
 mod macros;
 mod utils;
 
-pub use crate::context::blocked::BlockedClient;
+pub use crate::args::{FromValkeyArg, ValkeyArgs};
+pub use crate::context::acl::{
+    AclChannelFlags, AclLogEntryReason, AclRules, AclUser, ModuleUser, UserContext,
+};
+pub use crate::context::blocked::{
+    BlockOnKeysFlags, BlockedClient, CancellationToken, MeasureGuard,
+};
+pub use crate::context::call_builder::{CallArg, CallBuilder};
+pub use crate::context::event_loop::{EventLoopHandle, EventLoopMask};
+pub use crate::context::fork::{ForkChild, ForkExitStatus, ForkHandle};
 pub use crate::context::thread_safe::{
     ContextGuard, DetachedFromClient, ThreadSafeContext, ValkeyGILGuard, ValkeyLockIndicator,
 };
+pub use crate::de::{from_call_reply, from_call_result};
+pub use crate::dict::{DictIter, DictKey, ModuleDict};
 pub use crate::raw::NotifyEvent;
+pub use crate::redisvalue::FromValkeyValue;
+pub use crate::ser::to_value;
 
 pub use crate::configuration::ConfigurationValue;
 pub use crate::configuration::EnumConfigurationValue;
@@ -42,6 +61,12 @@ pub use crate::context::call_reply::{CallReply, CallResult, ErrorReply, PromiseC
 pub use crate::context::commands;
 pub use crate::context::info::ServerInfo;
 pub use crate::context::keys_cursor::KeysCursor;
+#[cfg(all(any(
+    feature = "min-valkey-compatibility-version-8-0",
+    feature = "min-redis-compatibility-version-7-2"
+)))]
+pub use crate::context::rdb::{RdbFlags, RdbStream};
+pub use crate::context::reply::ReplyWriter;
 pub use crate::context::server_events;
 pub use crate::context::AclPermissions;
 #[cfg(all(any(
@@ -230,6 +230,10 @@ pub fn use_redis_module_api() -> bool {
 
 ///////////////////////////////////////////////////////////////
 
+/// Passed as the length of an aggregate reply whose length is set later,
+/// once all its elements were written.
+pub const POSTPONED_LEN: c_long = REDISMODULE_POSTPONED_LEN as c_long;
+
 pub const FMT: *const c_char = b"v\0".as_ptr().cast::<c_char>();
 
 // REDISMODULE_HASH_DELETE is defined explicitly here because bindgen cannot
@@ -407,7 +411,10 @@ pub fn reply_with_map(ctx: *mut RedisModuleCtx, len: c_long) -> Status {
     unsafe {
         RedisModule_ReplyWithMap
             .map_or_else(
-                || RedisModule_ReplyWithArray.unwrap()(ctx, len * 2),
+                || {
+                    let len = if len == POSTPONED_LEN { len } else { len * 2 };
+                    RedisModule_ReplyWithArray.unwrap()(ctx, len)
+                },
                 |f| f(ctx, len),
             )
             .into()
@@ -433,6 +440,51 @@ pub fn reply_with_attribute(ctx: *mut RedisModuleCtx, len: c_long) -> Status {
     unsafe { RedisModule_ReplyWithAttribute.unwrap()(ctx, len).into() }
 }
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[inline]
+pub fn reply_set_array_length(ctx: *mut RedisModuleCtx, len: c_long) {
+    unsafe { RedisModule_ReplySetArrayLength.unwrap()(ctx, len) }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[inline]
+pub fn reply_set_map_length(ctx: *mut RedisModuleCtx, len: c_long) {
+    unsafe {
+        RedisModule_ReplySetMapLength.map_or_else(
+            || RedisModule_ReplySetArrayLength.unwrap()(ctx, len * 2),
+            |f| f(ctx, len),
+        )
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[inline]
+pub fn reply_set_set_length(ctx: *mut RedisModuleCtx, len: c_long) {
+    unsafe {
+        RedisModule_ReplySetSetLength.map_or_else(
+            || RedisModule_ReplySetArrayLength.unwrap()(ctx, len),
+            |f| f(ctx, len),
+        )
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[inline]
+pub fn reply_set_attribute_length(ctx: *mut RedisModuleCtx, len: c_long) {
+    unsafe { RedisModule_ReplySetAttributeLength.unwrap()(ctx, len) }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[inline]
+pub fn reply_set_push_length(ctx: *mut RedisModuleCtx, len: c_long) {
+    unsafe {
+        RedisModule_ReplySetPushLength.map_or_else(
+            || RedisModule_ReplySetArrayLength.unwrap()(ctx, len),
+            |f| f(ctx, len),
+        )
+    }
+}
+
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub fn reply_with_error(ctx: *mut RedisModuleCtx, err: *const c_char) {
     unsafe {
@@ -1,14 +1,112 @@
 use crate::context::call_reply::{ErrorCallReply, ErrorReply};
 pub use crate::raw;
+use std::borrow::Cow;
 use std::ffi::CStr;
 use std::fmt;
 
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ValkeyError {
     WrongArity,
     Str(&'static str),
     String(String),
     WrongType,
+    Coded(CodedError),
+}
+
+/// An error with an explicit error code, such as `ERR`, `WRONGTYPE`, `BUSY` or a
+/// module specific code like `MYMOD_NOTFOUND`. The code is sent as the first word
+/// of the error reply, which is what clients branch on and what the server counts
+/// in `INFO errorstats`.
+///
+/// A [CodedError] returned through `?` keeps its code. For this reason it does
+/// not implement [std::error::Error], which would convert it like any other
+/// error, replied with the `ERR` code.
+///
+/// ```rust,no_run,ignore
+/// let item = find_item(&name)?;
+/// ```
+#[derive(Debug)]
+pub struct CodedError {
+    code: Cow<'static, str>,
+    message: String,
+    source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+}
+
+impl CodedError {
+    /// Creates an error with the given code and message. The code should be a
+    /// single upper case word.
+    pub fn new<C: Into<Cow<'static, str>>, M: Into<String>>(code: C, message: M) -> Self {
+        Self {
+            code: code.into(),
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Sets the underlying cause of this error, returned by [CodedError::source].
+    #[must_use]
+    pub fn with_source<E>(mut self, source: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+    {
+        self.source = Some(source.into());
+        self
+    }
+
+    #[must_use]
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Converts the error into a [ValkeyError::Coded], replied with its code.
+    #[must_use]
+    pub fn into_valkey_error(self) -> ValkeyError {
+        ValkeyError::Coded(self)
+    }
+
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the underlying cause of this error, if any.
+    #[must_use]
+    pub fn source(&self) -> Option<&(dyn std::error::Error + Send + Sync + 'static)> {
+        self.source.as_deref()
+    }
+}
+
+impl fmt::Display for CodedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.code, self.message)
+    }
+}
+
+impl From<CodedError> for ValkeyError {
+    fn from(e: CodedError) -> Self {
+        Self::Coded(e)
+    }
+}
+
+/// The error of the serde [Serializer](crate::ser::Serializer) and
+/// [Deserializer](crate::de::Deserializer), a [CodedError] implementing
+/// [std::error::Error] as serde requires.
+#[derive(Debug)]
+pub struct SerdeError(pub CodedError);
+
+impl fmt::Display for SerdeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for SerdeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0
+            .source()
+            .map(|e| e as &(dyn std::error::Error + 'static))
+    }
 }
 
 impl<'root> From<ErrorCallReply<'root>> for ValkeyError {
@@ -39,6 +137,30 @@ impl ValkeyError {
     pub const fn short_read() -> Self {
         Self::Str("ERR short read or OOM loading DB")
     }
+
+    /// Creates a [ValkeyError::Coded] error, see [CodedError].
+    #[must_use]
+    pub fn coded<C: Into<Cow<'static, str>>, M: Into<String>>(code: C, message: M) -> Self {
+        Self::Coded(CodedError::new(code, message))
+    }
+
+    /// Returns the error code the error is replied with, the first word of the reply.
+    #[must_use]
+    pub fn code(&self) -> Cow<'_, str> {
+        match self {
+            Self::WrongArity => Cow::Borrowed("ERR"),
+            Self::Coded(e) => Cow::Borrowed(e.code()),
+            e => {
+                let s = e.to_string();
+                let code = s.split(' ').next().unwrap_or_default();
+                if code.is_empty() {
+                    Cow::Borrowed("ERR")
+                } else {
+                    Cow::Owned(code.to_owned())
+                }
+            }
+        }
+    }
 }
 
 impl<T: std::error::Error> From<T> for ValkeyError {
@@ -62,8 +184,81 @@ impl fmt::Display for ValkeyError {
             .unwrap(),
             Self::Str(s) => s,
             Self::String(s) => s.as_str(),
+            Self::Coded(e) => return write!(f, "{e}"),
         };
 
         write!(f, "{d}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[derive(Debug)]
+    struct Missing;
+
+    impl fmt::Display for Missing {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "missing")
+        }
+    }
+
+    impl Error for Missing {}
+
+    #[test]
+    fn coded_error_keeps_its_code() {
+        let err = CodedError::new("MYMOD_NOTFOUND", "no such item").into_valkey_error();
+
+        assert!(matches!(err, ValkeyError::Coded(_)));
+        assert_eq!(err.code(), "MYMOD_NOTFOUND");
+        assert_eq!(err.to_string(), "MYMOD_NOTFOUND no such item");
+    }
+
+    #[test]
+    fn other_errors_get_the_default_code() {
+        let err: ValkeyError = Missing.into();
+
+        assert!(matches!(err, ValkeyError::String(_)));
+        assert_eq!(err.code(), "ERR");
+        assert_eq!(err.to_string(), "ERR missing");
+        assert_eq!(ValkeyError::WrongType.code(), "WRONGTYPE");
+        assert_eq!(ValkeyError::WrongArity.code(), "ERR");
+    }
+
+    #[test]
+    fn borrowing_errors_convert() {
+        fn lock(mutex: &std::sync::Mutex<i64>) -> Result<i64, ValkeyError> {
+            Ok(*mutex.lock()?)
+        }
+
+        let mutex = std::sync::Mutex::new(1);
+        assert_eq!(lock(&mutex).unwrap(), 1);
+    }
+
+    #[test]
+    fn question_mark_keeps_the_code() {
+        fn find() -> Result<i64, CodedError> {
+            Err(CodedError::new("MYMOD_NOTFOUND", "no such item"))
+        }
+        fn handler() -> Result<i64, ValkeyError> {
+            Ok(find()?)
+        }
+
+        let err = handler().unwrap_err();
+        assert!(matches!(err, ValkeyError::Coded(_)));
+        assert_eq!(err.code(), "MYMOD_NOTFOUND");
+        assert_eq!(err.to_string(), "MYMOD_NOTFOUND no such item");
+    }
+
+    #[test]
+    fn coded_error_chains_its_source() {
+        let err = CodedError::new("BUSY", "try again").with_source(Missing);
+
+        assert_eq!(err.code(), "BUSY");
+        assert_eq!(err.message(), "try again");
+        assert_eq!(err.source().unwrap().to_string(), "missing");
+        assert!(CodedError::new("ERR", "x").source().is_none());
+    }
+}
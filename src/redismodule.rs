@@ -1,19 +1,22 @@
 use std::borrow::Borrow;
 use std::ffi::CString;
 use std::fmt::Display;
+use std::iter::Peekable;
 use std::ops::Deref;
 use std::os::raw::{c_char, c_int, c_void};
 use std::ptr::{null_mut, NonNull};
 use std::slice;
 use std::str;
+use std::str::FromStr;
 use std::str::Utf8Error;
 use std::string::FromUtf8Error;
+use std::time::Duration;
 use std::{fmt, ptr};
 
 use serde::de::{Error, SeqAccess};
 
 pub use crate::raw;
-pub use crate::rediserror::ValkeyError;
+pub use crate::rediserror::{CodedError, SerdeError, ValkeyError};
 pub use crate::redisvalue::ValkeyValue;
 use crate::Context;
 
@@ -40,59 +43,209 @@ pub const TYPE_METHOD_VERSION: u64 = raw::REDISMODULE_TYPE_METHOD_VERSION as u64
 pub const AUTH_HANDLED: i32 = raw::REDISMODULE_AUTH_HANDLED as i32;
 pub const AUTH_NOT_HANDLED: i32 = raw::REDISMODULE_AUTH_NOT_HANDLED as i32;
 
+/// Parses the command arguments one at a time.
+///
+/// Implemented for any iterator of [ValkeyString], and for [ArgCursor]. Plain
+/// iterators such as `args.into_iter().skip(1)` do not track argument positions
+/// and can not peek, so [ArgCursor] is required for parsing errors to report the
+/// position of the invalid argument, and for [ArgCursor::peek] and
+/// [ArgCursor::next_if_keyword]. Use `ArgCursor::new(args).skip(1)` for those.
 pub trait NextArg {
     fn next_arg(&mut self) -> Result<ValkeyString, ValkeyError>;
-    fn next_string(&mut self) -> Result<String, ValkeyError>;
-    fn next_str<'a>(&mut self) -> Result<&'a str, ValkeyError>;
-    fn next_i64(&mut self) -> Result<i64, ValkeyError>;
-    fn next_u64(&mut self) -> Result<u64, ValkeyError>;
-    fn next_f64(&mut self) -> Result<f64, ValkeyError>;
-    fn done(&mut self) -> Result<(), ValkeyError>;
-}
 
-impl<T> NextArg for T
-where
-    T: Iterator<Item = ValkeyString>,
-{
-    #[inline]
-    fn next_arg(&mut self) -> Result<ValkeyString, ValkeyError> {
-        self.next().ok_or(ValkeyError::WrongArity)
+    /// Returns the position of the last returned argument, when it is tracked.
+    /// Parsing errors include this position. Only [ArgCursor] tracks it, plain
+    /// iterators return `None`.
+    fn position(&self) -> Option<usize> {
+        None
     }
 
     #[inline]
     fn next_string(&mut self) -> Result<String, ValkeyError> {
-        self.next()
-            .map_or(Err(ValkeyError::WrongArity), |v| Ok(v.to_string_lossy()))
+        self.next_arg().map(|v| v.to_string_lossy())
     }
 
     #[inline]
     fn next_str<'a>(&mut self) -> Result<&'a str, ValkeyError> {
-        self.next()
-            .map_or(Err(ValkeyError::WrongArity), |v| v.try_as_str())
+        let arg = self.next_arg()?;
+        arg.try_as_str()
+            .map_err(|e| at_position(e, self.position()))
+    }
+
+    #[inline]
+    fn next_bytes(&mut self) -> Result<Vec<u8>, ValkeyError> {
+        self.next_arg().map(Vec::from)
     }
 
     #[inline]
     fn next_i64(&mut self) -> Result<i64, ValkeyError> {
-        self.next()
-            .map_or(Err(ValkeyError::WrongArity), |v| v.parse_integer())
+        let arg = self.next_arg()?;
+        arg.parse_integer()
+            .map_err(|e| at_position(e, self.position()))
     }
 
     #[inline]
     fn next_u64(&mut self) -> Result<u64, ValkeyError> {
-        self.next()
-            .map_or(Err(ValkeyError::WrongArity), |v| v.parse_unsigned_integer())
+        let arg = self.next_arg()?;
+        arg.parse_unsigned_integer()
+            .map_err(|e| at_position(e, self.position()))
     }
 
     #[inline]
     fn next_f64(&mut self) -> Result<f64, ValkeyError> {
-        self.next()
-            .map_or(Err(ValkeyError::WrongArity), |v| v.parse_float())
+        let arg = self.next_arg()?;
+        arg.parse_float()
+            .map_err(|e| at_position(e, self.position()))
+    }
+
+    /// Parses `1`/`0`, `true`/`false` or `yes`/`no`, case-insensitively.
+    fn next_bool(&mut self) -> Result<bool, ValkeyError> {
+        let arg = self.next_arg()?;
+        let arg = arg.as_slice();
+        if [b"1".as_slice(), b"true", b"yes"]
+            .iter()
+            .any(|v| arg.eq_ignore_ascii_case(v))
+        {
+            Ok(true)
+        } else if [b"0".as_slice(), b"false", b"no"]
+            .iter()
+            .any(|v| arg.eq_ignore_ascii_case(v))
+        {
+            Ok(false)
+        } else {
+            Err(at_position(
+                ValkeyError::Str("Couldn't parse as boolean"),
+                self.position(),
+            ))
+        }
+    }
+
+    /// Parses the next argument with [FromStr]. Case-insensitive matching,
+    /// if needed, is up to the [FromStr] implementation.
+    fn next_enum<E: FromStr>(&mut self) -> Result<E, ValkeyError> {
+        let arg = self.next_arg()?;
+        arg.try_as_str()
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| at_position(ValkeyError::Str("Couldn't parse as enum"), self.position()))
+    }
+
+    /// Parses a non-negative number of milliseconds.
+    #[inline]
+    fn next_duration_ms(&mut self) -> Result<Duration, ValkeyError> {
+        self.next_u64().map(Duration::from_millis)
+    }
+
+    /// Parses a non-negative number of seconds.
+    #[inline]
+    fn next_duration_secs(&mut self) -> Result<Duration, ValkeyError> {
+        self.next_u64().map(Duration::from_secs)
+    }
+
+    /// Returns the next `n` arguments, or an arity error if there are fewer left.
+    fn next_n(&mut self, n: usize) -> Result<Vec<ValkeyString>, ValkeyError> {
+        (0..n).map(|_| self.next_arg()).collect()
+    }
+
+    /// Consumes and returns all the remaining arguments.
+    fn remaining(&mut self) -> Vec<ValkeyString> {
+        std::iter::from_fn(|| self.next_arg().ok()).collect()
     }
 
     /// Return an error if there are any more arguments
     #[inline]
     fn done(&mut self) -> Result<(), ValkeyError> {
-        self.next().map_or(Ok(()), |_| Err(ValkeyError::WrongArity))
+        self.next_arg()
+            .map_or(Ok(()), |_| Err(ValkeyError::WrongArity))
+    }
+}
+
+/// Appends the argument position, when known, to a parsing error.
+fn at_position(err: ValkeyError, position: Option<usize>) -> ValkeyError {
+    match (err, position) {
+        (ValkeyError::Str(s), Some(position)) => {
+            ValkeyError::String(format!("{s} at argument {position}"))
+        }
+        (ValkeyError::String(s), Some(position)) => {
+            ValkeyError::String(format!("{s} at argument {position}"))
+        }
+        (err, _) => err,
+    }
+}
+
+impl<T> NextArg for T
+where
+    T: Iterator<Item = ValkeyString>,
+{
+    #[inline]
+    fn next_arg(&mut self) -> Result<ValkeyString, ValkeyError> {
+        self.next().ok_or(ValkeyError::WrongArity)
+    }
+}
+
+/// A [NextArg] over the command arguments which supports peeking and keyword
+/// matching, and tracks the position of each argument so parsing errors can
+/// report it. Positions are indexes into the arguments the cursor was created
+/// with, so the command name is at position 0.
+pub struct ArgCursor {
+    args: Peekable<std::vec::IntoIter<ValkeyString>>,
+    consumed: usize,
+}
+
+impl ArgCursor {
+    pub fn new(args: Vec<ValkeyString>) -> Self {
+        Self {
+            args: args.into_iter().peekable(),
+            consumed: 0,
+        }
+    }
+
+    /// Skips the next `n` arguments, usually the command name. Skipped
+    /// arguments still count for the positions reported in parsing errors.
+    #[must_use]
+    pub fn skip(mut self, n: usize) -> Self {
+        for _ in 0..n {
+            if self.next_arg().is_err() {
+                break;
+            }
+        }
+        self
+    }
+
+    /// Returns the next argument without consuming it.
+    pub fn peek(&mut self) -> Option<&ValkeyString> {
+        self.args.peek()
+    }
+
+    /// Consumes the next argument only if it matches the given keyword,
+    /// case-insensitively. Returns whether it was consumed.
+    pub fn next_if_keyword(&mut self, keyword: &str) -> bool {
+        let matches = self
+            .peek()
+            .is_some_and(|v| v.as_slice().eq_ignore_ascii_case(keyword.as_bytes()));
+        if matches {
+            let _ = self.next_arg();
+        }
+        matches
+    }
+}
+
+impl From<Vec<ValkeyString>> for ArgCursor {
+    fn from(args: Vec<ValkeyString>) -> Self {
+        Self::new(args)
+    }
+}
+
+impl NextArg for ArgCursor {
+    #[inline]
+    fn next_arg(&mut self) -> Result<ValkeyString, ValkeyError> {
+        let arg = self.args.next().ok_or(ValkeyError::WrongArity)?;
+        self.consumed += 1;
+        Ok(arg)
+    }
+
+    fn position(&self) -> Option<usize> {
+        self.consumed.checked_sub(1)
     }
 }
 
@@ -171,6 +324,35 @@ impl ValkeyString {
         Self { ctx, inner }
     }
 
+    /// Holds the string with `RedisModule_HoldString`, which is usually a cheap
+    /// ref-count increase and only copies the string when it can not be retained
+    /// (for example, a string that is owned by the automatic memory management).
+    /// Like [Self::safe_clone], the context is only taken as proof the Valkey GIL
+    /// is held, since the ref count is not atomic. It is not passed on, so the
+    /// returned string is not bound to any context and can be kept for as long
+    /// as needed.
+    pub fn hold(&self, _ctx: &Context) -> Self {
+        let inner = unsafe { raw::RedisModule_HoldString.unwrap()(ptr::null_mut(), self.inner) };
+        Self {
+            ctx: ptr::null_mut(),
+            inner,
+        }
+    }
+
+    /// Trims the extra memory allocated for the string (for example, when it was
+    /// created by the networking code). Should be called on strings that are kept
+    /// for a long time.
+    ///
+    /// # Safety
+    ///
+    /// The string may be reallocated, so this handle must be the sole owner of
+    /// the underlying string: no other [ValkeyString] may share it, as the ones
+    /// returned by [Self::hold] or [Self::safe_clone] do, and the server must
+    /// not reference it either. The Valkey GIL must be held.
+    pub unsafe fn trim_allocation(&mut self) {
+        unsafe { raw::RedisModule_TrimStringAllocation.unwrap()(self.inner) }
+    }
+
     /// Creates a ValkeyString from a &str and retains it.  This is useful in cases where Modules need to pass ownership of a ValkeyString to the core engine without it being freed when we drop a ValkeyString
     pub fn create_and_retain(arg: &str) -> ValkeyString {
         let arg = ValkeyString::create(None, arg);
@@ -318,6 +500,12 @@ impl Borrow<str> for ValkeyString {
     }
 }
 
+impl Borrow<[u8]> for ValkeyString {
+    fn borrow(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
 impl Clone for ValkeyString {
     fn clone(&self) -> Self {
         let inner =
@@ -449,6 +637,26 @@ mod tests {
         assert_eq!(cloned.as_slice(), b"value");
     }
 
+    #[test]
+    fn hold_keeps_string_alive_after_original_is_dropped() {
+        let context = Context::test();
+        let original = ValkeyString::test("value");
+        let mut held = original.hold(&context);
+        drop(original);
+
+        unsafe { held.trim_allocation() };
+        assert_eq!(held.as_slice(), b"value");
+    }
+
+    #[test]
+    fn strings_can_be_looked_up_by_bytes() {
+        let mut index = std::collections::HashMap::new();
+        index.insert(ValkeyString::test("key"), 1);
+
+        assert_eq!(index.get(b"key".as_slice()), Some(&1));
+        assert_eq!(index.get(b"other".as_slice()), None);
+    }
+
     #[test]
     fn create_and_retain_keeps_the_transferred_reference_alive() {
         let _context = Context::test();
@@ -628,4 +836,89 @@ mod tests {
 
         assert!(matches!(args.done(), Err(ValkeyError::WrongArity)));
     }
+
+    #[test]
+    fn next_arg_helpers_parse_booleans_enums_and_durations() {
+        let mut args = vec![
+            ValkeyString::test("YES"),
+            ValkeyString::test("0"),
+            ValkeyString::test("2"),
+            ValkeyString::test("1500"),
+            ValkeyString::test("3"),
+            ValkeyString::test("bytes"),
+        ]
+        .into_iter();
+
+        assert!(args.next_bool().expect("boolean should parse"));
+        assert!(!args.next_bool().expect("boolean should parse"));
+        assert_eq!(args.next_enum::<u8>().expect("enum should parse"), 2);
+        assert_eq!(
+            args.next_duration_ms().expect("duration should parse"),
+            Duration::from_millis(1500)
+        );
+        assert_eq!(
+            args.next_duration_secs().expect("duration should parse"),
+            Duration::from_secs(3)
+        );
+        assert_eq!(
+            args.next_bytes().expect("bytes should be returned"),
+            b"bytes"
+        );
+        assert!(args.done().is_ok());
+    }
+
+    #[test]
+    fn next_n_and_remaining_consume_multiple_arguments() {
+        let mut args = vec![
+            ValkeyString::test("k1"),
+            ValkeyString::test("v1"),
+            ValkeyString::test("k2"),
+            ValkeyString::test("v2"),
+        ]
+        .into_iter();
+
+        assert_eq!(args.next_n(2).expect("two arguments are left").len(), 2);
+        assert_eq!(args.remaining().len(), 2);
+        assert!(matches!(args.next_n(1), Err(ValkeyError::WrongArity)));
+        assert!(args.remaining().is_empty());
+    }
+
+    #[test]
+    fn arg_cursor_peeks_and_matches_keywords() {
+        let mut args = ArgCursor::new(vec![
+            ValkeyString::test("zrange"),
+            ValkeyString::test("key"),
+            ValkeyString::test("withscores"),
+        ]);
+
+        assert_eq!(args.position(), None);
+        assert_eq!(
+            args.next_string().expect("name should be returned"),
+            "zrange"
+        );
+        assert_eq!(args.peek().map(|v| v.as_slice()), Some(b"key".as_slice()));
+        assert!(!args.next_if_keyword("WITHSCORES"));
+        assert_eq!(args.next_string().expect("key should be returned"), "key");
+        assert!(args.next_if_keyword("WITHSCORES"));
+        assert_eq!(args.position(), Some(2));
+        assert!(args.peek().is_none());
+        assert!(args.done().is_ok());
+    }
+
+    #[test]
+    fn arg_cursor_reports_argument_position_on_parse_errors() {
+        let mut args = ArgCursor::new(vec![
+            ValkeyString::test("cmd"),
+            ValkeyString::test("10"),
+            ValkeyString::test("maybe"),
+        ])
+        .skip(1);
+
+        assert_eq!(args.next_i64().expect("integer should parse"), 10);
+        assert!(matches!(
+            args.next_bool(),
+            Err(ValkeyError::String(s)) if s == "Couldn't parse as boolean at argument 2"
+        ));
+        assert!(matches!(args.next_i64(), Err(ValkeyError::WrongArity)));
+    }
 }
@@ -17,6 +17,7 @@ pub enum ValkeyValueKey {
 }
 
 #[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
 pub enum ValkeyValue {
     SimpleStringStatic(&'static str),
     SimpleString(String),
@@ -33,7 +34,16 @@ pub enum ValkeyValue {
     Map(HashMap<ValkeyValueKey, ValkeyValue>),
     Set(HashSet<ValkeyValueKey>),
     OrderedMap(BTreeMap<ValkeyValueKey, ValkeyValue>),
+    /// A map whose entries are replied in the given order.
+    MapEntries(Vec<(ValkeyValueKey, ValkeyValue)>),
     OrderedSet(BTreeSet<ValkeyValueKey>),
+    /// A value annotated with RESP3 attributes, RESP2 clients only get the value.
+    WithAttributes {
+        attrs: BTreeMap<ValkeyValueKey, ValkeyValue>,
+        value: Box<ValkeyValue>,
+    },
+    /// A RESP3 push message, RESP2 clients get it as an array.
+    Push(Vec<ValkeyValue>),
     Null,
     NoReply, // No reply at all (as opposed to a Null reply)
 }
@@ -282,6 +292,12 @@ impl<'root> From<&CallResult<'root>> for ValkeyValue {
     }
 }
 
+impl<'root> From<CallResult<'root>> for ValkeyValue {
+    fn from(reply: CallResult<'root>) -> Self {
+        (&reply).into()
+    }
+}
+
 impl<'root> TryFrom<&CallResult<'root>> for ValkeyValueKey {
     type Error = ValkeyError;
     fn try_from(reply: &CallResult<'root>) -> Result<Self, Self::Error> {
@@ -296,11 +312,332 @@ impl<'root> TryFrom<&CallResult<'root>> for ValkeyValueKey {
     }
 }
 
+/// Types that can be built out of a [ValkeyValue], the reverse of [Into<ValkeyValue>].
+///
+/// Usually implemented on structs and enums with `#[derive(FromValkeyValue)]`
+/// from `valkey_module_macros`, which also implements [TryFrom<ValkeyValue>].
+pub trait FromValkeyValue: Sized {
+    fn from_valkey_value(value: ValkeyValue) -> Result<Self, ValkeyError>;
+
+    /// Builds the type out of a borrowed value, which lets several types be
+    /// tried on the same value, as the derive does for the variants of an enum.
+    /// The default implementation converts a clone of the value, implementations
+    /// only reading the value should avoid it.
+    fn from_valkey_value_ref(value: &ValkeyValue) -> Result<Self, ValkeyError> {
+        Self::from_valkey_value(value.clone())
+    }
+}
+
+impl FromValkeyValue for ValkeyValue {
+    fn from_valkey_value(value: ValkeyValue) -> Result<Self, ValkeyError> {
+        Ok(value)
+    }
+}
+
+impl FromValkeyValue for String {
+    fn from_valkey_value(value: ValkeyValue) -> Result<Self, ValkeyError> {
+        value.try_into()
+    }
+
+    fn from_valkey_value_ref(value: &ValkeyValue) -> Result<Self, ValkeyError> {
+        as_str(value)
+            .map(str::to_owned)
+            .ok_or(ValkeyError::Str("Can not convert result to String"))
+    }
+}
+
+impl FromValkeyValue for i64 {
+    fn from_valkey_value(value: ValkeyValue) -> Result<Self, ValkeyError> {
+        Self::from_valkey_value_ref(&value)
+    }
+
+    fn from_valkey_value_ref(value: &ValkeyValue) -> Result<Self, ValkeyError> {
+        match value {
+            ValkeyValue::Integer(i) => Ok(*i),
+            value => as_str(value)
+                .and_then(|s| s.parse().ok())
+                .ok_or(ValkeyError::Str("ERR expected an integer value")),
+        }
+    }
+}
+
+macro_rules! from_valkey_value_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl FromValkeyValue for $ty {
+                fn from_valkey_value(value: ValkeyValue) -> Result<Self, ValkeyError> {
+                    Self::from_valkey_value_ref(&value)
+                }
+
+                fn from_valkey_value_ref(value: &ValkeyValue) -> Result<Self, ValkeyError> {
+                    <$ty>::try_from(i64::from_valkey_value_ref(value)?)
+                        .map_err(|_| ValkeyError::Str("ERR integer value out of range"))
+                }
+            }
+        )*
+    };
+}
+
+from_valkey_value_integer!(i32, u32, u64, usize);
+
+impl FromValkeyValue for f64 {
+    fn from_valkey_value(value: ValkeyValue) -> Result<Self, ValkeyError> {
+        Self::from_valkey_value_ref(&value)
+    }
+
+    fn from_valkey_value_ref(value: &ValkeyValue) -> Result<Self, ValkeyError> {
+        match value {
+            ValkeyValue::Float(f) => Ok(*f),
+            ValkeyValue::Integer(i) => Ok(*i as f64),
+            value => as_str(value)
+                .and_then(|s| s.parse().ok())
+                .ok_or(ValkeyError::Str("ERR expected a float value")),
+        }
+    }
+}
+
+impl FromValkeyValue for bool {
+    fn from_valkey_value(value: ValkeyValue) -> Result<Self, ValkeyError> {
+        Self::from_valkey_value_ref(&value)
+    }
+
+    fn from_valkey_value_ref(value: &ValkeyValue) -> Result<Self, ValkeyError> {
+        match value {
+            ValkeyValue::Bool(b) => Ok(*b),
+            ValkeyValue::Integer(0) => Ok(false),
+            ValkeyValue::Integer(1) => Ok(true),
+            value => match as_str(value) {
+                Some("0" | "false") => Ok(false),
+                Some("1" | "true") => Ok(true),
+                _ => Err(ValkeyError::Str("ERR expected a boolean value")),
+            },
+        }
+    }
+}
+
+impl<T: FromValkeyValue> FromValkeyValue for Option<T> {
+    fn from_valkey_value(value: ValkeyValue) -> Result<Self, ValkeyError> {
+        match value {
+            ValkeyValue::Null => Ok(None),
+            value => T::from_valkey_value(value).map(Some),
+        }
+    }
+
+    fn from_valkey_value_ref(value: &ValkeyValue) -> Result<Self, ValkeyError> {
+        match value {
+            ValkeyValue::Null => Ok(None),
+            value => T::from_valkey_value_ref(value).map(Some),
+        }
+    }
+}
+
+impl<T: FromValkeyValue> FromValkeyValue for Vec<T> {
+    fn from_valkey_value(value: ValkeyValue) -> Result<Self, ValkeyError> {
+        match value {
+            ValkeyValue::Array(items) | ValkeyValue::Push(items) => {
+                items.into_iter().map(T::from_valkey_value).collect()
+            }
+            ValkeyValue::Set(items) => items
+                .into_iter()
+                .map(|k| T::from_valkey_value(k.into()))
+                .collect(),
+            ValkeyValue::OrderedSet(items) => items
+                .into_iter()
+                .map(|k| T::from_valkey_value(k.into()))
+                .collect(),
+            _ => Err(ValkeyError::Str("ERR expected an array value")),
+        }
+    }
+
+    fn from_valkey_value_ref(value: &ValkeyValue) -> Result<Self, ValkeyError> {
+        match value {
+            ValkeyValue::Array(items) | ValkeyValue::Push(items) => {
+                items.iter().map(T::from_valkey_value_ref).collect()
+            }
+            ValkeyValue::Set(items) => items
+                .iter()
+                .map(|k| T::from_valkey_value(k.clone().into()))
+                .collect(),
+            ValkeyValue::OrderedSet(items) => items
+                .iter()
+                .map(|k| T::from_valkey_value(k.clone().into()))
+                .collect(),
+            _ => Err(ValkeyError::Str("ERR expected an array value")),
+        }
+    }
+}
+
+impl<T: FromValkeyValue + Eq + Hash> FromValkeyValue for HashSet<T> {
+    fn from_valkey_value(value: ValkeyValue) -> Result<Self, ValkeyError> {
+        Ok(Vec::<T>::from_valkey_value(value)?.into_iter().collect())
+    }
+
+    fn from_valkey_value_ref(value: &ValkeyValue) -> Result<Self, ValkeyError> {
+        Ok(Vec::<T>::from_valkey_value_ref(value)?
+            .into_iter()
+            .collect())
+    }
+}
+
+impl<T: FromValkeyValue + Ord> FromValkeyValue for BTreeSet<T> {
+    fn from_valkey_value(value: ValkeyValue) -> Result<Self, ValkeyError> {
+        Ok(Vec::<T>::from_valkey_value(value)?.into_iter().collect())
+    }
+
+    fn from_valkey_value_ref(value: &ValkeyValue) -> Result<Self, ValkeyError> {
+        Ok(Vec::<T>::from_valkey_value_ref(value)?
+            .into_iter()
+            .collect())
+    }
+}
+
+impl<K: FromValkeyValue + Eq + Hash, V: FromValkeyValue> FromValkeyValue for HashMap<K, V> {
+    fn from_valkey_value(value: ValkeyValue) -> Result<Self, ValkeyError> {
+        map_entries(value)?
+            .into_iter()
+            .map(|(k, v)| Ok((K::from_valkey_value(k.into())?, V::from_valkey_value(v)?)))
+            .collect()
+    }
+
+    fn from_valkey_value_ref(value: &ValkeyValue) -> Result<Self, ValkeyError> {
+        map_entries_ref(value)?
+            .into_iter()
+            .map(|(k, v)| {
+                Ok((
+                    K::from_valkey_value(k.into())?,
+                    V::from_valkey_value_ref(v)?,
+                ))
+            })
+            .collect()
+    }
+}
+
+impl<K: FromValkeyValue + Ord, V: FromValkeyValue> FromValkeyValue for BTreeMap<K, V> {
+    fn from_valkey_value(value: ValkeyValue) -> Result<Self, ValkeyError> {
+        map_entries(value)?
+            .into_iter()
+            .map(|(k, v)| Ok((K::from_valkey_value(k.into())?, V::from_valkey_value(v)?)))
+            .collect()
+    }
+
+    fn from_valkey_value_ref(value: &ValkeyValue) -> Result<Self, ValkeyError> {
+        map_entries_ref(value)?
+            .into_iter()
+            .map(|(k, v)| {
+                Ok((
+                    K::from_valkey_value(k.into())?,
+                    V::from_valkey_value_ref(v)?,
+                ))
+            })
+            .collect()
+    }
+}
+
+impl From<ValkeyValueKey> for ValkeyValue {
+    fn from(key: ValkeyValueKey) -> Self {
+        match key {
+            ValkeyValueKey::Integer(i) => Self::Integer(i),
+            ValkeyValueKey::String(s) => Self::BulkString(s),
+            ValkeyValueKey::BulkValkeyString(s) => Self::BulkValkeyString(s),
+            ValkeyValueKey::BulkString(b) => Self::StringBuffer(b),
+            ValkeyValueKey::Bool(b) => Self::Bool(b),
+        }
+    }
+}
+
+/// Returns the entries of a map value. A flat array of keys and values, as
+/// maps are represented on RESP2, is also accepted.
+#[doc(hidden)]
+pub fn map_entries(value: ValkeyValue) -> Result<Vec<(ValkeyValueKey, ValkeyValue)>, ValkeyError> {
+    match value {
+        ValkeyValue::Map(map) => Ok(map.into_iter().collect()),
+        ValkeyValue::OrderedMap(map) => Ok(map.into_iter().collect()),
+        ValkeyValue::MapEntries(entries) => Ok(entries),
+        ValkeyValue::Array(items) => {
+            if items.len() % 2 != 0 {
+                return Err(ValkeyError::Str("ERR expected an array of keys and values"));
+            }
+            let mut items = items.into_iter();
+            let mut entries = Vec::new();
+            while let (Some(k), Some(v)) = (items.next(), items.next()) {
+                entries.push((ValkeyValueKey::try_from(k)?, v));
+            }
+            Ok(entries)
+        }
+        _ => Err(ValkeyError::Str("ERR expected a map value")),
+    }
+}
+
+/// Returns the entries of a borrowed map value, only the keys are cloned. A
+/// flat array of keys and values is also accepted, see [map_entries].
+#[doc(hidden)]
+pub fn map_entries_ref(
+    value: &ValkeyValue,
+) -> Result<Vec<(ValkeyValueKey, &ValkeyValue)>, ValkeyError> {
+    match value {
+        ValkeyValue::Map(map) => Ok(map.iter().map(|(k, v)| (k.clone(), v)).collect()),
+        ValkeyValue::OrderedMap(map) => Ok(map.iter().map(|(k, v)| (k.clone(), v)).collect()),
+        ValkeyValue::MapEntries(entries) => {
+            Ok(entries.iter().map(|(k, v)| (k.clone(), v)).collect())
+        }
+        ValkeyValue::Array(items) => {
+            if items.len() % 2 != 0 {
+                return Err(ValkeyError::Str("ERR expected an array of keys and values"));
+            }
+            items
+                .chunks(2)
+                .map(|kv| Ok((ValkeyValueKey::try_from(kv[0].clone())?, &kv[1])))
+                .collect()
+        }
+        _ => Err(ValkeyError::Str("ERR expected a map value")),
+    }
+}
+
+/// Returns the string held by a value, if it holds a valid UTF-8 string.
+#[doc(hidden)]
+pub fn as_str(value: &ValkeyValue) -> Option<&str> {
+    match value {
+        ValkeyValue::SimpleStringStatic(s) => Some(*s),
+        ValkeyValue::SimpleString(s) | ValkeyValue::BulkString(s) => Some(s.as_str()),
+        ValkeyValue::BulkValkeyString(s) => s.try_as_str().ok(),
+        ValkeyValue::StringBuffer(b) => std::str::from_utf8(b).ok(),
+        _ => None,
+    }
+}
+
+/// Removes and returns the value of the entry whose key is `name`.
+#[doc(hidden)]
+pub fn take_entry<V>(entries: &mut Vec<(ValkeyValueKey, V)>, name: &str) -> Option<V> {
+    let pos = entries.iter().position(|(k, _)| match k {
+        ValkeyValueKey::String(s) => s == name,
+        ValkeyValueKey::BulkValkeyString(s) => s.as_slice() == name.as_bytes(),
+        ValkeyValueKey::BulkString(b) => b == name.as_bytes(),
+        ValkeyValueKey::Integer(_) | ValkeyValueKey::Bool(_) => false,
+    })?;
+    Some(entries.swap_remove(pos).1)
+}
+
+impl TryFrom<ValkeyValue> for ValkeyValueKey {
+    type Error = ValkeyError;
+    fn try_from(value: ValkeyValue) -> Result<Self, ValkeyError> {
+        match value {
+            ValkeyValue::Integer(i) => Ok(Self::Integer(i)),
+            ValkeyValue::Bool(b) => Ok(Self::Bool(b)),
+            ValkeyValue::BulkValkeyString(s) => Ok(Self::BulkValkeyString(s)),
+            ValkeyValue::StringBuffer(b) => Ok(Self::BulkString(b)),
+            value => String::try_from(value)
+                .map(Self::String)
+                .map_err(|_| ValkeyError::Str("ERR value can not be used as a map key")),
+        }
+    }
+}
+
 //////////////////////////////////////////////////////////
 
 #[cfg(test)]
 mod tests {
-    use super::ValkeyValue;
+    use super::{FromValkeyValue, ValkeyValue};
+    use std::collections::BTreeMap;
 
     #[test]
     fn from_vec_string() {
@@ -347,4 +684,80 @@ mod tests {
     fn from_option_none() {
         assert_eq!(ValkeyValue::from(None::<()>), ValkeyValue::Null,);
     }
+
+    #[test]
+    fn scalars_from_value() {
+        assert_eq!(
+            i64::from_valkey_value(ValkeyValue::Integer(-3)).unwrap(),
+            -3
+        );
+        assert_eq!(u32::from_valkey_value("7".into()).unwrap(), 7);
+        assert_eq!(
+            f64::from_valkey_value(ValkeyValue::Integer(2)).unwrap(),
+            2.0
+        );
+        assert!(bool::from_valkey_value(ValkeyValue::Integer(1)).unwrap());
+        assert!(u32::from_valkey_value(ValkeyValue::Integer(-1)).is_err());
+        assert!(i64::from_valkey_value("abc".into()).is_err());
+    }
+
+    #[test]
+    fn collections_from_value() {
+        assert_eq!(
+            Vec::<Option<String>>::from_valkey_value(ValkeyValue::Array(vec![
+                "a".into(),
+                ValkeyValue::Null
+            ]))
+            .unwrap(),
+            vec![Some("a".to_owned()), None]
+        );
+
+        // maps are also read from flat arrays of keys and values
+        let map = BTreeMap::<String, i64>::from_valkey_value(ValkeyValue::Array(vec![
+            "a".into(),
+            ValkeyValue::Integer(1),
+            "b".into(),
+            ValkeyValue::Integer(2),
+        ]))
+        .unwrap();
+        assert_eq!(
+            map,
+            BTreeMap::from([("a".to_owned(), 1), ("b".to_owned(), 2)])
+        );
+        assert!(
+            BTreeMap::<String, i64>::from_valkey_value(ValkeyValue::Array(vec!["a".into()]))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn collections_from_borrowed_value() {
+        let value = ValkeyValue::Array(vec![
+            "a".into(),
+            ValkeyValue::Integer(1),
+            "b".into(),
+            ValkeyValue::Integer(2),
+        ]);
+
+        assert_eq!(
+            BTreeMap::<String, i64>::from_valkey_value_ref(&value).unwrap(),
+            BTreeMap::from([("a".to_owned(), 1), ("b".to_owned(), 2)])
+        );
+        // integers are not strings, the value is left as is for the next attempt
+        assert!(Vec::<String>::from_valkey_value_ref(&value).is_err());
+        assert_eq!(
+            Vec::<ValkeyValue>::from_valkey_value_ref(&value)
+                .unwrap()
+                .len(),
+            4
+        );
+        assert_eq!(
+            Vec::<Option<String>>::from_valkey_value_ref(&ValkeyValue::Array(vec![
+                "a".into(),
+                ValkeyValue::Null
+            ]))
+            .unwrap(),
+            vec![Some("a".to_owned()), None]
+        );
+    }
 }
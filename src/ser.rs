@@ -0,0 +1,484 @@
+use std::fmt::Display;
+
+use serde::ser::{self, Serialize};
+
+use crate::redisvalue::ValkeyValueKey;
+use crate::{CodedError, SerdeError, ValkeyError, ValkeyResult, ValkeyValue};
+
+/// Converts any [Serialize] type into a [ValkeyValue], so it can be returned
+/// from a command.
+///
+/// Structs and maps become maps, keeping the order of their fields, which are
+/// replied as RESP3 maps or as flat arrays of keys and values to RESP2 clients.
+/// Sequences and tuples become arrays, `None` and `()` become null, unit enum
+/// variants become their name and other enum variants become a single entry map
+/// keyed by the variant name. Integers that don't fit an `i64` are replied as
+/// big numbers.
+///
+/// ```rust,no_run,ignore
+/// #[derive(Serialize)]
+/// struct Item {
+///     name: String,
+///     #[serde(rename = "qty")]
+///     quantity: u32,
+///     #[serde(skip)]
+///     cache: Vec<u8>,
+/// }
+///
+/// fn get_item(ctx: &Context, args: Vec<ValkeyString>) -> ValkeyResult {
+///     let item = load_item(ctx, &args[1])?;
+///     valkey_module::to_value(&item)
+/// }
+/// ```
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> ValkeyResult {
+    value
+        .serialize(Serializer)
+        .map_err(|e| ValkeyError::Coded(e.0))
+}
+
+impl ser::Error for SerdeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        SerdeError(CodedError::new("ERR", msg.to_string()))
+    }
+}
+
+/// A [serde::Serializer] producing a [ValkeyValue], see [to_value].
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+    type Ok = ValkeyValue;
+    type Error = SerdeError;
+
+    type SerializeSeq = SerializeVec;
+    type SerializeTuple = SerializeVec;
+    type SerializeTupleStruct = SerializeVec;
+    type SerializeTupleVariant = SerializeTupleVariant;
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeMap;
+    type SerializeStructVariant = SerializeStructVariant;
+
+    fn serialize_bool(self, v: bool) -> Result<ValkeyValue, SerdeError> {
+        Ok(ValkeyValue::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<ValkeyValue, SerdeError> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<ValkeyValue, SerdeError> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<ValkeyValue, SerdeError> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<ValkeyValue, SerdeError> {
+        Ok(ValkeyValue::Integer(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<ValkeyValue, SerdeError> {
+        Ok(i64::try_from(v).map_or_else(
+            |_| ValkeyValue::BigNumber(v.to_string()),
+            ValkeyValue::Integer,
+        ))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<ValkeyValue, SerdeError> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<ValkeyValue, SerdeError> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<ValkeyValue, SerdeError> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<ValkeyValue, SerdeError> {
+        self.serialize_u128(u128::from(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<ValkeyValue, SerdeError> {
+        Ok(i64::try_from(v).map_or_else(
+            |_| ValkeyValue::BigNumber(v.to_string()),
+            ValkeyValue::Integer,
+        ))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<ValkeyValue, SerdeError> {
+        self.serialize_f64(f64::from(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<ValkeyValue, SerdeError> {
+        Ok(ValkeyValue::Float(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<ValkeyValue, SerdeError> {
+        Ok(ValkeyValue::BulkString(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<ValkeyValue, SerdeError> {
+        Ok(ValkeyValue::BulkString(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<ValkeyValue, SerdeError> {
+        Ok(ValkeyValue::StringBuffer(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<ValkeyValue, SerdeError> {
+        Ok(ValkeyValue::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<ValkeyValue, SerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<ValkeyValue, SerdeError> {
+        Ok(ValkeyValue::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<ValkeyValue, SerdeError> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<ValkeyValue, SerdeError> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<ValkeyValue, SerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<ValkeyValue, SerdeError> {
+        Ok(variant_map(variant, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeVec, SerdeError> {
+        Ok(SerializeVec {
+            vec: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeVec, SerdeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeVec, SerdeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeTupleVariant, SerdeError> {
+        Ok(SerializeTupleVariant {
+            variant,
+            vec: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeMap, SerdeError> {
+        Ok(SerializeMap {
+            entries: Vec::with_capacity(len.unwrap_or(0)),
+            next_key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeMap, SerdeError> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeStructVariant, SerdeError> {
+        Ok(SerializeStructVariant {
+            variant,
+            entries: Vec::with_capacity(len),
+        })
+    }
+}
+
+/// An enum variant holding data is written as a map with a single entry,
+/// keyed by the variant name.
+fn variant_map(variant: &'static str, value: ValkeyValue) -> ValkeyValue {
+    ValkeyValue::MapEntries(vec![(ValkeyValueKey::String(variant.to_owned()), value)])
+}
+
+/// Only strings, integers and booleans can be used as map keys.
+fn to_key<T: Serialize + ?Sized>(key: &T) -> Result<ValkeyValueKey, SerdeError> {
+    match key.serialize(Serializer)? {
+        ValkeyValue::BulkString(s) => Ok(ValkeyValueKey::String(s)),
+        ValkeyValue::StringBuffer(b) => Ok(ValkeyValueKey::BulkString(b)),
+        ValkeyValue::Integer(i) => Ok(ValkeyValueKey::Integer(i)),
+        ValkeyValue::Bool(b) => Ok(ValkeyValueKey::Bool(b)),
+        _ => Err(ser::Error::custom(
+            "map key must be a string, an integer or a boolean",
+        )),
+    }
+}
+
+pub struct SerializeVec {
+    vec: Vec<ValkeyValue>,
+}
+
+impl ser::SerializeSeq for SerializeVec {
+    type Ok = ValkeyValue;
+    type Error = SerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.vec.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<ValkeyValue, SerdeError> {
+        Ok(ValkeyValue::Array(self.vec))
+    }
+}
+
+impl ser::SerializeTuple for SerializeVec {
+    type Ok = ValkeyValue;
+    type Error = SerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<ValkeyValue, SerdeError> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeVec {
+    type Ok = ValkeyValue;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<ValkeyValue, SerdeError> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+pub struct SerializeTupleVariant {
+    variant: &'static str,
+    vec: Vec<ValkeyValue>,
+}
+
+impl ser::SerializeTupleVariant for SerializeTupleVariant {
+    type Ok = ValkeyValue;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.vec.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<ValkeyValue, SerdeError> {
+        Ok(variant_map(self.variant, ValkeyValue::Array(self.vec)))
+    }
+}
+
+pub struct SerializeMap {
+    entries: Vec<(ValkeyValueKey, ValkeyValue)>,
+    next_key: Option<ValkeyValueKey>,
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = ValkeyValue;
+    type Error = SerdeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), SerdeError> {
+        self.next_key = Some(to_key(key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| ser::Error::custom("map value serialized before its key"))?;
+        self.entries.push((key, value.serialize(Serializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<ValkeyValue, SerdeError> {
+        Ok(ValkeyValue::MapEntries(self.entries))
+    }
+}
+
+impl ser::SerializeStruct for SerializeMap {
+    type Ok = ValkeyValue;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        self.entries.push((
+            ValkeyValueKey::String(key.to_owned()),
+            value.serialize(Serializer)?,
+        ));
+        Ok(())
+    }
+
+    fn end(self) -> Result<ValkeyValue, SerdeError> {
+        Ok(ValkeyValue::MapEntries(self.entries))
+    }
+}
+
+pub struct SerializeStructVariant {
+    variant: &'static str,
+    entries: Vec<(ValkeyValueKey, ValkeyValue)>,
+}
+
+impl ser::SerializeStructVariant for SerializeStructVariant {
+    type Ok = ValkeyValue;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        self.entries.push((
+            ValkeyValueKey::String(key.to_owned()),
+            value.serialize(Serializer)?,
+        ));
+        Ok(())
+    }
+
+    fn end(self) -> Result<ValkeyValue, SerdeError> {
+        Ok(variant_map(
+            self.variant,
+            ValkeyValue::MapEntries(self.entries),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+    use std::collections::HashMap;
+
+    fn key(s: &str) -> ValkeyValueKey {
+        ValkeyValueKey::String(s.to_owned())
+    }
+
+    #[derive(Serialize)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Rect(i64, i64),
+        Named { name: String },
+    }
+
+    #[derive(Serialize)]
+    struct Item {
+        name: String,
+        #[serde(rename = "qty")]
+        quantity: u32,
+        #[serde(skip)]
+        #[allow(dead_code)]
+        cache: Vec<u8>,
+        tag: Option<String>,
+        shape: Shape,
+    }
+
+    #[test]
+    fn serializes_struct_as_map() {
+        let item = Item {
+            name: "box".to_owned(),
+            quantity: 3,
+            cache: vec![1, 2],
+            tag: None,
+            shape: Shape::Empty,
+        };
+
+        // The fields are kept in declaration order
+        let expected = vec![
+            (key("name"), ValkeyValue::BulkString("box".to_owned())),
+            (key("qty"), ValkeyValue::Integer(3)),
+            (key("tag"), ValkeyValue::Null),
+            (key("shape"), ValkeyValue::BulkString("Empty".to_owned())),
+        ];
+        assert_eq!(to_value(&item).unwrap(), ValkeyValue::MapEntries(expected));
+    }
+
+    #[test]
+    fn serializes_enum_variants_and_tuples() {
+        assert_eq!(
+            to_value(&Shape::Circle(1.5)).unwrap(),
+            variant_map("Circle", ValkeyValue::Float(1.5))
+        );
+        assert_eq!(
+            to_value(&Shape::Rect(1, 2)).unwrap(),
+            variant_map(
+                "Rect",
+                ValkeyValue::Array(vec![ValkeyValue::Integer(1), ValkeyValue::Integer(2)])
+            )
+        );
+        assert_eq!(
+            to_value(&Shape::Named {
+                name: "a".to_owned()
+            })
+            .unwrap(),
+            variant_map(
+                "Named",
+                ValkeyValue::MapEntries(vec![(
+                    key("name"),
+                    ValkeyValue::BulkString("a".to_owned())
+                )])
+            )
+        );
+        assert_eq!(
+            to_value(&(true, u64::MAX)).unwrap(),
+            ValkeyValue::Array(vec![
+                ValkeyValue::Bool(true),
+                ValkeyValue::BigNumber(u64::MAX.to_string())
+            ])
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_map_keys() {
+        let map = HashMap::from([(vec![1], 1)]);
+
+        let err = to_value(&map).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ERR map key must be a string, an integer or a boolean"
+        );
+    }
+}
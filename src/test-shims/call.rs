@@ -104,6 +104,7 @@ impl TestCallReplyValue {
             ),
             ValkeyValue::Map(values) => Self::Map(Self::map_entries(values)?),
             ValkeyValue::OrderedMap(values) => Self::Map(Self::map_entries(values)?),
+            ValkeyValue::MapEntries(values) => Self::Map(Self::map_entries(values)?),
             ValkeyValue::Null => Self::Null,
             ValkeyValue::StaticError(value) => Self::Error(value.as_bytes().to_vec()),
             _ => return Err("test-shim calls do not support this reply type"),
@@ -13,6 +13,7 @@ pub(super) fn install() {
     unsafe {
         raw::RedisModule_GetThreadSafeContext = Some(get_thread_safe_context);
         raw::RedisModule_ThreadSafeContextLock = Some(thread_safe_context_lock);
+        raw::RedisModule_ThreadSafeContextTryLock = Some(thread_safe_context_try_lock);
         raw::RedisModule_ThreadSafeContextUnlock = Some(thread_safe_context_unlock);
         raw::RedisModule_FreeThreadSafeContext = Some(free_thread_safe_context);
     }
@@ -144,6 +145,14 @@ pub(super) extern "C" fn thread_safe_context_lock(ctx: *mut raw::RedisModuleCtx)
     });
 }
 
+/// Never contended, as no main thread holds the GIL in tests.
+pub(super) extern "C" fn thread_safe_context_try_lock(
+    ctx: *mut raw::RedisModuleCtx,
+) -> libc::c_int {
+    thread_safe_context_lock(ctx);
+    raw::REDISMODULE_OK as libc::c_int
+}
+
 pub(super) extern "C" fn thread_safe_context_unlock(_ctx: *mut raw::RedisModuleCtx) {
     PENDING_LOCK_STATE.with(|pending| {
         *pending.borrow_mut() = PendingLockState::Unlocked;
@@ -445,6 +454,29 @@ mod tests {
         assert!(matches!(reply, Ok(ValkeyValue::Integer(1))));
     }
 
+    #[test]
+    fn try_lock_exposes_configured_context() {
+        let mut context = ThreadSafeContext::test();
+        context.expect_call("INCR", &["counter"], ValkeyValue::Integer(1));
+
+        let guard = context
+            .try_lock()
+            .expect("an uncontended context should be locked");
+        assert!(matches!(
+            guard.call("INCR", &["counter"]),
+            Ok(ValkeyValue::Integer(1))
+        ));
+        drop(guard);
+
+        let guard = context
+            .lock_timeout(std::time::Duration::ZERO)
+            .expect("an uncontended context should be locked without waiting");
+        assert!(matches!(
+            guard.call("INCR", &["counter"]),
+            Ok(ValkeyValue::Integer(1))
+        ));
+    }
+
     #[test]
     fn configured_call_expectation_is_replayed_for_each_lock() {
         let mut context = ThreadSafeContext::test();
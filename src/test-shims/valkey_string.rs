@@ -11,6 +11,8 @@ pub(super) fn install() {
         raw::RedisModule_StringPtrLen = Some(string_ptr_len);
         raw::RedisModule_FreeString = Some(free_string);
         raw::RedisModule_RetainString = Some(retain_string);
+        raw::RedisModule_HoldString = Some(hold_string);
+        raw::RedisModule_TrimStringAllocation = Some(trim_string_allocation);
         raw::RedisModule_StringToLongLong = Some(string_to_longlong);
         raw::RedisModule_StringToULongLong = Some(string_to_ulonglong);
         raw::RedisModule_StringToDouble = Some(string_to_double);
@@ -88,6 +90,22 @@ pub(super) extern "C" fn retain_string(
     }
 }
 
+/// Adds one owner to a shim-backed module string and returns the same string.
+///
+/// Shim strings can always be retained, so this never needs to copy the string.
+pub(super) extern "C" fn hold_string(
+    ctx: *mut raw::RedisModuleCtx,
+    string: *mut raw::RedisModuleString,
+) -> *mut raw::RedisModuleString {
+    retain_string(ctx, string);
+    string
+}
+
+/// Accepts a shim-backed module string without changing it.
+///
+/// Shim strings are allocated with their exact size, so there is nothing to trim.
+pub(super) extern "C" fn trim_string_allocation(_string: *mut raw::RedisModuleString) {}
+
 /// Parses a shim-backed string as a signed C `long long` value.
 pub(super) extern "C" fn string_to_longlong(
     string: *const raw::RedisModuleString,
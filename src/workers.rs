@@ -0,0 +1,209 @@
+//! A bounded pool of worker threads, running the jobs of blocked clients off
+//! the main thread.
+//!
+//! The pool is typically created in the module `init`, sized from module
+//! configurations, and shut down in `deinit`, see `examples/workers.rs`.
+
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::{BlockedClient, InfoContext, ThreadSafeContext, ValkeyError, ValkeyResult};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+struct Queue {
+    jobs: VecDeque<Job>,
+    shutdown: bool,
+}
+
+struct Shared {
+    queue: Mutex<Queue>,
+    job_queued: Condvar,
+    active: AtomicUsize,
+    submitted: AtomicU64,
+    completed: AtomicU64,
+    rejected: AtomicU64,
+}
+
+/// The metrics of a [Pool], see [Pool::stats].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStats {
+    /// The number of threads of the pool.
+    pub threads: usize,
+    /// The maximum number of jobs waiting for a thread.
+    pub queue_depth: usize,
+    /// The number of jobs waiting for a thread.
+    pub queued: usize,
+    /// The number of jobs running.
+    pub active: usize,
+    /// The number of jobs submitted since the pool was created.
+    pub submitted: u64,
+    /// The number of jobs completed since the pool was created.
+    pub completed: u64,
+    /// The number of jobs rejected because the queue was full.
+    pub rejected: u64,
+}
+
+/// A fixed number of threads, running the jobs given to [Pool::submit] in
+/// order. At most `queue_depth` jobs wait for a thread, further jobs are
+/// rejected with a `BUSY` error until the pool catches up.
+pub struct Pool {
+    name: String,
+    threads: usize,
+    queue_depth: usize,
+    shared: Arc<Shared>,
+    handles: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl Pool {
+    /// Starts a pool of `threads` threads, named after the pool. The name is
+    /// also the name of its `INFO` section, see [Pool::add_info].
+    pub fn new(name: &str, threads: usize, queue_depth: usize) -> ValkeyResult<Pool> {
+        if threads == 0 {
+            return Err(ValkeyError::Str(
+                "ERR a worker pool needs at least one thread",
+            ));
+        }
+        let pool = Pool {
+            name: name.to_owned(),
+            threads,
+            queue_depth,
+            shared: Arc::new(Shared {
+                queue: Mutex::new(Queue {
+                    jobs: VecDeque::with_capacity(queue_depth),
+                    shutdown: false,
+                }),
+                job_queued: Condvar::new(),
+                active: AtomicUsize::new(0),
+                submitted: AtomicU64::new(0),
+                completed: AtomicU64::new(0),
+                rejected: AtomicU64::new(0),
+            }),
+            handles: Mutex::new(Vec::with_capacity(threads)),
+        };
+        for i in 0..threads {
+            let shared = Arc::clone(&pool.shared);
+            let handle = thread::Builder::new()
+                .name(format!("{name}-{i}"))
+                .spawn(move || run_jobs(&shared))
+                .map_err(|e| {
+                    ValkeyError::String(format!("ERR failed to start the worker thread: {e}"))
+                })?;
+            pool.handles.lock().unwrap().push(handle);
+        }
+        Ok(pool)
+    }
+
+    /// Returns the name of the pool.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Queues `job` to run on one of the threads of the pool, then replies to
+    /// the blocked client with its result. Must be called on the main thread,
+    /// with a client blocked by the running command.
+    ///
+    /// If the queue is full, or the pool was shut down, the client is unblocked
+    /// and an error is returned for the command to reply with.
+    pub fn submit<F>(&self, blocked_client: BlockedClient, job: F) -> ValkeyResult<()>
+    where
+        F: FnOnce(&ThreadSafeContext<BlockedClient>) -> ValkeyResult + Send + 'static,
+    {
+        let mut queue = self.shared.queue.lock().unwrap();
+        let error = if queue.shutdown {
+            ValkeyError::Str("ERR the worker pool is shut down")
+        } else if queue.jobs.len() >= self.queue_depth {
+            self.shared.rejected.fetch_add(1, Ordering::Relaxed);
+            ValkeyError::String(format!("BUSY the worker pool '{}' is full", self.name))
+        } else {
+            queue.jobs.push_back(Box::new(move || {
+                let thread_ctx = ThreadSafeContext::with_blocked_client(blocked_client);
+                let response = panic::catch_unwind(AssertUnwindSafe(|| job(&thread_ctx)))
+                    .unwrap_or(Err(ValkeyError::Str("ERR the worker job panicked")));
+                thread_ctx.reply(response);
+            }));
+            self.shared.submitted.fetch_add(1, Ordering::Relaxed);
+            drop(queue);
+            self.shared.job_queued.notify_one();
+            return Ok(());
+        };
+        drop(queue);
+        let _ = blocked_client.abort();
+        Err(error)
+    }
+
+    /// Returns the current metrics of the pool.
+    pub fn stats(&self) -> PoolStats {
+        PoolStats {
+            threads: self.threads,
+            queue_depth: self.queue_depth,
+            queued: self.shared.queue.lock().unwrap().jobs.len(),
+            active: self.shared.active.load(Ordering::Relaxed),
+            submitted: self.shared.submitted.load(Ordering::Relaxed),
+            completed: self.shared.completed.load(Ordering::Relaxed),
+            rejected: self.shared.rejected.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Adds the metrics of the pool to `INFO`, in a section named after the pool.
+    pub fn add_info(&self, ctx: &InfoContext) -> ValkeyResult<()> {
+        let stats = self.stats();
+        ctx.builder()
+            .add_section(&self.name)
+            .field("threads", stats.threads as u64)?
+            .field("queue_depth", stats.queue_depth as u64)?
+            .field("queued", stats.queued as u64)?
+            .field("active", stats.active as u64)?
+            .field("submitted", stats.submitted)?
+            .field("completed", stats.completed)?
+            .field("rejected", stats.rejected)?
+            .build_section()?
+            .build_info()?;
+        Ok(())
+    }
+
+    /// Stops accepting jobs, and waits for the threads to run the queued jobs
+    /// and stop. Meant to be called from the module `deinit`, which the server
+    /// only calls once no client is blocked, so no job is left by then.
+    ///
+    /// A job waiting for the GIL, which the caller holds, would never complete,
+    /// so this must not be called while jobs remain.
+    pub fn shutdown(&self) {
+        self.shared.queue.lock().unwrap().shutdown = true;
+        self.shared.job_queued.notify_all();
+        let handles = std::mem::take(&mut *self.handles.lock().unwrap());
+        for handle in handles {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Pool {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+fn run_jobs(shared: &Shared) {
+    loop {
+        let job = {
+            let mut queue = shared.queue.lock().unwrap();
+            loop {
+                if let Some(job) = queue.jobs.pop_front() {
+                    break job;
+                }
+                if queue.shutdown {
+                    return;
+                }
+                queue = shared.job_queued.wait(queue).unwrap();
+            }
+        };
+        shared.active.fetch_add(1, Ordering::Relaxed);
+        job();
+        shared.active.fetch_sub(1, Ordering::Relaxed);
+        shared.completed.fetch_add(1, Ordering::Relaxed);
+    }
+}
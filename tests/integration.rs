@@ -51,6 +51,169 @@ fn test_block() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_block_with_callbacks() -> Result<()> {
+    let mut con = start_server_w_module_get_connection("block")?;
+
+    let res: i64 = redis::cmd("block.sum")
+        .arg(&[100, 10, 0])
+        .query(&mut con)
+        .with_context(|| "failed to run block.sum")?;
+    assert_eq!(res, 5050);
+
+    let res: i64 = redis::cmd("block.sum")
+        .arg(&[10, 10, 5000])
+        .query(&mut con)
+        .with_context(|| "failed to run block.sum")?;
+    assert_eq!(res, 55);
+
+    let res: RedisResult<i64> = redis::cmd("block.sum")
+        .arg(&[10, 1000, 100])
+        .query(&mut con);
+    assert!(res.unwrap_err().to_string().contains("timed out"));
+
+    // The time spent on the worker thread is accounted to the command
+    redis::cmd("CONFIG")
+        .arg(&["SET", "slowlog-log-slower-than", "50000"])
+        .exec(&mut con)?;
+    redis::cmd("SLOWLOG").arg(&["RESET"]).exec(&mut con)?;
+    let res: i64 = redis::cmd("block.sum")
+        .arg(&[10, 100, 0])
+        .query(&mut con)
+        .with_context(|| "failed to run block.sum")?;
+    assert_eq!(res, 55);
+    let res: Vec<Vec<Value>> = redis::cmd("SLOWLOG").arg(&["GET", "1"]).query(&mut con)?;
+    let duration: i64 = redis::from_redis_value(&res[0][2])?;
+    let args: Vec<String> = redis::from_redis_value(&res[0][3])?;
+    assert!(duration >= 100_000, "duration {duration}us");
+    assert_eq!(args[0], "block.sum");
+
+    // Also in the command stats, unlike the time spent in the background
+    // without measuring it
+    redis::cmd("CONFIG").arg(&["RESETSTAT"]).exec(&mut con)?;
+    let res: i64 = redis::cmd("block.sum")
+        .arg(&[10, 200, 0])
+        .query(&mut con)
+        .with_context(|| "failed to run block.sum")?;
+    assert_eq!(res, 55);
+    let res: i64 = redis::cmd("block")
+        .query(&mut con)
+        .with_context(|| "failed to run block")?;
+    assert_eq!(res, 42);
+    let stats: String = redis::cmd("INFO").arg("commandstats").query(&mut con)?;
+    let usec_per_call = |command: &str| -> Result<f64> {
+        let line = stats
+            .lines()
+            .find(|line| line.starts_with(&format!("cmdstat_{command}:")))
+            .with_context(|| format!("no stats for {command}"))?;
+        let value = line
+            .split(',')
+            .find_map(|field| field.strip_prefix("usec_per_call="))
+            .with_context(|| format!("no usec_per_call for {command}"))?;
+        Ok(value.parse()?)
+    };
+    let measured = usec_per_call("block.sum")?;
+    assert!(measured >= 200_000.0, "usec_per_call {measured}");
+    let unmeasured = usec_per_call("block")?;
+    assert!(unmeasured < 100_000.0, "usec_per_call {unmeasured}");
+
+    Ok(())
+}
+
+#[test]
+fn test_block_disconnect() -> Result<()> {
+    let server = start_server_w_module_get_connection("block")?;
+    let port = server.port;
+    let (_guard, mut con) = server.into_parts();
+
+    let res: i64 = redis::cmd("block.scan")
+        .arg(2)
+        .query(&mut con)
+        .with_context(|| "failed to run block.scan")?;
+    assert_eq!(res, 2);
+
+    // The client goes away while its scan runs
+    let mut blocked_con =
+        get_valkey_connection(port).with_context(|| FAILED_TO_CONNECT_TO_SERVER)?;
+    blocked_con.set_read_timeout(Some(Duration::from_millis(100)))?;
+    let res: RedisResult<i64> = redis::cmd("block.scan").arg(1000).query(&mut blocked_con);
+    assert!(res.is_err());
+    drop(blocked_con);
+
+    let start = Instant::now();
+    loop {
+        let res: (i64, i64) = redis::cmd("block.scan_stats").query(&mut con)?;
+        if res == (1, 1) {
+            break;
+        }
+        if start.elapsed() >= EVENT_WAIT_TIMEOUT {
+            anyhow::bail!("scan was not cancelled, stats: {res:?}");
+        }
+        thread::sleep(EVENT_POLL_INTERVAL);
+    }
+    wait_for_no_blocked_clients(&mut con)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_block_on_keys() -> Result<()> {
+    let server = start_server_w_module_get_connection("block")?;
+    let port = server.port;
+    let (_guard, mut con) = server.into_parts();
+
+    // Served right away from a non empty list
+    redis::cmd("RPUSH").arg("list2").arg("a").exec(&mut con)?;
+    let res: (String, String) = redis::cmd("block.lpop")
+        .arg("list1")
+        .arg("list2")
+        .arg(0)
+        .query(&mut con)
+        .with_context(|| "failed to run block.lpop")?;
+    assert_eq!(res, ("list2".to_owned(), "a".to_owned()));
+
+    // Times out
+    let res: Option<String> = redis::cmd("block.lpop")
+        .arg("list1")
+        .arg(100)
+        .query(&mut con)
+        .with_context(|| "failed to run block.lpop")?;
+    assert_eq!(res, None);
+
+    // Blocks until one of the lists is pushed to
+    let mut blocked_con =
+        get_valkey_connection(port).with_context(|| FAILED_TO_CONNECT_TO_SERVER)?;
+    let handle = thread::spawn(move || -> RedisResult<(String, String)> {
+        redis::cmd("block.lpop")
+            .arg("list1")
+            .arg("list2")
+            .arg(0)
+            .query(&mut blocked_con)
+    });
+    wait_for_blocked_clients(&mut con)?;
+    redis::cmd("RPUSH").arg("list1").arg("b").exec(&mut con)?;
+    let res = handle.join().unwrap()?;
+    assert_eq!(res, ("list1".to_owned(), "b".to_owned()));
+
+    // Stays blocked on writes, and is unblocked once the key is deleted
+    let mut blocked_con =
+        get_valkey_connection(port).with_context(|| FAILED_TO_CONNECT_TO_SERVER)?;
+    let handle = thread::spawn(move || -> RedisResult<i64> {
+        redis::cmd("block.wait_del")
+            .arg("key")
+            .arg(0)
+            .query(&mut blocked_con)
+    });
+    wait_for_blocked_clients(&mut con)?;
+    redis::cmd("RPUSH").arg("key").arg("c").exec(&mut con)?;
+    redis::cmd("DEL").arg("key").exec(&mut con)?;
+    let res = handle.join().unwrap()?;
+    assert_eq!(res, 1);
+    wait_for_no_blocked_clients(&mut con)?;
+
+    Ok(())
+}
+
 #[test]
 fn test_info() -> Result<()> {
     let mut con = start_server_w_module_get_connection("info")?;
@@ -143,6 +306,110 @@ fn test_load_unload() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_workers() -> Result<()> {
+    let server = start_server_w_module_get_connection("hello")?;
+    let port = server.port;
+    let (_guard, mut con) = server.into_parts();
+    let module_path = get_module_path("workers")?;
+
+    let loaded: String = redis::cmd("MODULE")
+        .arg(&["LOADEX", &module_path])
+        .arg(&["CONFIG", "workers.threads", "1"])
+        .arg(&["CONFIG", "workers.queue-depth", "1"])
+        .query(&mut con)
+        .with_context(|| "failed to load the workers module")?;
+    assert_eq!(loaded, "OK");
+
+    let res: i64 = redis::cmd("workers.sleep")
+        .arg(1)
+        .query(&mut con)
+        .with_context(|| "failed to run workers.sleep")?;
+    assert_eq!(res, 1);
+
+    let pool_field = |con: &mut redis::Connection, field: &str| -> Result<u64> {
+        let info: String = redis::cmd("INFO").arg("workers").query(con)?;
+        info.lines()
+            .find_map(|line| line.strip_prefix(&format!("workers_{field}:")))
+            .and_then(|value| value.trim().parse().ok())
+            .with_context(|| format!("no {field} field in INFO workers"))
+    };
+
+    // One job runs and one waits, so the queue is full
+    let handles = (0..2)
+        .map(|_| -> Result<_> {
+            let mut blocked_con =
+                get_valkey_connection(port).with_context(|| FAILED_TO_CONNECT_TO_SERVER)?;
+            Ok(thread::spawn(move || -> RedisResult<i64> {
+                redis::cmd("workers.sleep").arg(500).query(&mut blocked_con)
+            }))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let start = Instant::now();
+    while pool_field(&mut con, "queued")? != 1 || pool_field(&mut con, "active")? != 1 {
+        if start.elapsed() >= EVENT_WAIT_TIMEOUT {
+            anyhow::bail!("the jobs were not queued");
+        }
+        thread::sleep(EVENT_POLL_INTERVAL);
+    }
+
+    let res: RedisResult<i64> = redis::cmd("workers.sleep").arg(1).query(&mut con);
+    assert_eq!(res.unwrap_err().code(), Some("BUSY"));
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap()?, 500);
+    }
+    assert_eq!(pool_field(&mut con, "threads")?, 1);
+    assert_eq!(pool_field(&mut con, "queue_depth")?, 1);
+    assert_eq!(pool_field(&mut con, "submitted")?, 3);
+    assert_eq!(pool_field(&mut con, "completed")?, 3);
+    assert_eq!(pool_field(&mut con, "rejected")?, 1);
+
+    // The threads of the pool are stopped on unload
+    let unloaded: String = redis::cmd("MODULE")
+        .arg(&["UNLOAD", "workers"])
+        .query(&mut con)?;
+    assert_eq!(unloaded, "OK");
+
+    Ok(())
+}
+
+#[test]
+fn test_event_loop() -> Result<()> {
+    let mut con = start_server_w_module_get_connection("event_loop")?;
+
+    for message in ["first", "second"] {
+        redis::cmd("event_loop.send")
+            .arg(message)
+            .exec(&mut con)
+            .with_context(|| "failed to run event_loop.send")?;
+    }
+
+    // Received in the background, in any order
+    let start = Instant::now();
+    loop {
+        let mut messages: Vec<String> = redis::cmd("LRANGE")
+            .arg(&["messages", "0", "-1"])
+            .query(&mut con)?;
+        messages.sort();
+        if messages == ["first", "second"] {
+            break;
+        }
+        if start.elapsed() >= EVENT_WAIT_TIMEOUT {
+            anyhow::bail!("the messages were not received: {messages:?}");
+        }
+        thread::sleep(EVENT_POLL_INTERVAL);
+    }
+
+    let res = redis::cmd("event_loop.send").arg("a\nb").exec(&mut con);
+    assert!(res.unwrap_err().to_string().contains("new line"));
+
+    let res = redis::cmd("event_loop.register_again").exec(&mut con);
+    assert!(res.unwrap_err().to_string().contains("already registered"));
+
+    Ok(())
+}
+
 #[test]
 fn test_timer() -> Result<()> {
     let mut con = start_server_w_module_get_connection("timer")?;
@@ -421,6 +688,53 @@ fn test_call() -> Result<()> {
 
     assert_eq!(&res, "pass");
 
+    redis::cmd("set")
+        .arg(&["x", "forwarded"])
+        .exec(&mut con)
+        .with_context(|| "failed to run set")?;
+
+    let res: String = redis::cmd("call.forward")
+        .arg(&["GET", "x"])
+        .query(&mut con)
+        .with_context(|| "failed to run call.forward")?;
+
+    assert_eq!(&res, "forwarded");
+
+    let res: Result<i64, RedisError> = redis::cmd("call.forward")
+        .arg(&["INCR", "x"])
+        .query(&mut con);
+
+    assert!(res
+        .expect_err("call.forward should forward the error reply")
+        .to_string()
+        .contains("not an integer"));
+
+    let res: Vec<u8> = redis::cmd("call.proto")
+        .arg(&["PING"])
+        .query(&mut con)
+        .with_context(|| "failed to run call.proto")?;
+
+    assert_eq!(res, b"+PONG\r\n");
+
+    redis::cmd("zadd")
+        .arg(&["z", "1.5", "a", "2", "b"])
+        .exec(&mut con)
+        .with_context(|| "failed to run zadd")?;
+
+    let res: Vec<(String, f64)> = redis::cmd("call.typed")
+        .arg(&["z"])
+        .query(&mut con)
+        .with_context(|| "failed to run call.typed")?;
+
+    assert_eq!(res, vec![("a".to_owned(), 1.5), ("b".to_owned(), 2.0)]);
+
+    let res: f64 = redis::cmd("call.builder")
+        .arg(&["z", "3.5", "c"])
+        .query(&mut con)
+        .with_context(|| "failed to run call.builder")?;
+
+    assert_eq!(res, 3.5);
+
     Ok(())
 }
 
@@ -481,6 +795,107 @@ fn test_verify_acl_on_user() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_acl_check_command_and_channel() -> Result<()> {
+    let mut con = start_server_w_module_get_connection("acl")?;
+
+    let res: String = redis::cmd("ACL")
+        .arg(&[
+            "SETUSER", "bob", "on", ">pass", "~*", "&news", "+get", "+publish",
+        ])
+        .query(&mut con)?;
+    assert_eq!(&res, "OK");
+
+    let _: String = redis::cmd("SET").arg(&["x", "1"]).query(&mut con)?;
+    let res: String = redis::cmd("acl.exec_as")
+        .arg(&["bob", "GET", "x"])
+        .query(&mut con)?;
+    assert_eq!(&res, "1");
+
+    let res: RedisResult<String> = redis::cmd("acl.exec_as")
+        .arg(&["bob", "SET", "x", "2"])
+        .query(&mut con);
+    let error = res.expect_err("bob should not be allowed to run SET");
+    assert_eq!(error.code(), Some("NOPERM"));
+
+    let res: i64 = redis::cmd("acl.publish_as")
+        .arg(&["bob", "news", "hello"])
+        .query(&mut con)?;
+    assert_eq!(res, 0);
+
+    let res: RedisResult<i64> = redis::cmd("acl.publish_as")
+        .arg(&["bob", "secret", "hello"])
+        .query(&mut con);
+    let error = res.expect_err("bob should not be allowed to publish to the channel");
+    assert_eq!(error.code(), Some("NOPERM"));
+
+    let log: Vec<Vec<Value>> = redis::cmd("ACL").arg(&["LOG"]).query(&mut con)?;
+    assert_eq!(log.len(), 2);
+    let entries: Vec<String> = log
+        .iter()
+        .map(|entry| {
+            entry
+                .iter()
+                .map(|v| match v {
+                    Value::BulkString(b) => String::from_utf8_lossy(b).into_owned(),
+                    _ => String::new(),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+    assert!(entries[0].contains("reason channel") && entries[0].contains("object secret"));
+    assert!(entries[1].contains("reason command") && entries[1].contains("object SET"));
+
+    Ok(())
+}
+
+#[test]
+fn test_module_user() -> Result<()> {
+    let mut con = start_server_w_module_get_connection("acl")?;
+
+    let _: String = redis::cmd("SET").arg(&["sandbox:1", "a"]).query(&mut con)?;
+    let _: String = redis::cmd("SET").arg(&["other", "b"]).query(&mut con)?;
+
+    let res: String = redis::cmd("acl.sandboxed_get")
+        .arg(&["sandbox:1"])
+        .query(&mut con)?;
+    assert_eq!(&res, "a");
+
+    let res: RedisResult<String> = redis::cmd("acl.sandboxed_get")
+        .arg(&["other"])
+        .query(&mut con);
+    let error = res.expect_err("the sandbox user should not access keys outside its namespace");
+    assert_eq!(error.code(), Some("NOPERM"));
+
+    // The sandbox user is restored after a nested user
+    let res: String = redis::cmd("acl.nested_sandboxed_get")
+        .arg(&["sandbox:1"])
+        .query(&mut con)?;
+    assert_eq!(&res, "a");
+    let res: RedisResult<String> = redis::cmd("acl.nested_sandboxed_get")
+        .arg(&["other"])
+        .query(&mut con);
+    let error =
+        res.expect_err("the restored sandbox user should not access keys outside its namespace");
+    assert_eq!(error.code(), Some("NOPERM"));
+
+    let _: i64 = redis::cmd("acl.sandbox_login").query(&mut con)?;
+    let res: String = redis::cmd("GET").arg(&["sandbox:1"]).query(&mut con)?;
+    assert_eq!(&res, "a");
+    let res: RedisResult<String> = redis::cmd("GET").arg(&["other"]).query(&mut con);
+    let error = res.expect_err("the sandboxed client should not access keys outside its namespace");
+    assert_eq!(error.code(), Some("NOPERM"));
+
+    let _: String = redis::cmd("AUTH")
+        .arg(&["default", "any"])
+        .query(&mut con)?;
+    let res: i64 = redis::cmd("acl.sandbox_logouts").query(&mut con)?;
+    assert_eq!(res, 1);
+
+    Ok(())
+}
+
 #[test]
 fn test_key_space_notifications() -> Result<()> {
     let mut con = start_server_w_module_get_connection("events")?;
@@ -526,6 +941,9 @@ fn test_context_mutex() -> Result<()> {
         .query(&mut con)?;
     assert_eq!(res, None);
 
+    let res: Vec<i64> = redis::cmd("try_lock_on_thread").query(&mut con)?;
+    assert_eq!(res, vec![0, 0, 1]);
+
     Ok(())
 }
 
@@ -651,6 +1069,117 @@ fn test_server_event_shutdown() -> Result<()> {
     Ok(())
 }
 
+fn wait_for_fork_status(con: &mut redis::Connection, expected: &str) -> Result<()> {
+    let deadline = Instant::now() + EVENT_WAIT_TIMEOUT;
+    loop {
+        let status: Option<String> = redis::cmd("fork.status").query(con)?;
+        if status.as_deref() == Some(expected) {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(anyhow::Error::msg(format!(
+                "timed out waiting for fork status '{expected}', got {status:?}"
+            )));
+        }
+        thread::sleep(EVENT_POLL_INTERVAL);
+    }
+}
+
+#[test]
+fn test_fork() -> Result<()> {
+    let mut con = start_server_w_module_get_connection("fork")?;
+
+    redis::cmd("fork.start")
+        .arg(&["50", "false"])
+        .exec(&mut con)?;
+    wait_for_fork_status(&mut con, "exited:0")?;
+
+    redis::cmd("fork.start")
+        .arg(&["50", "true"])
+        .exec(&mut con)?;
+    wait_for_fork_status(&mut con, "exited:1")?;
+
+    redis::cmd("fork.start")
+        .arg(&["60000", "false"])
+        .exec(&mut con)?;
+    let res: RedisResult<String> = redis::cmd("fork.start")
+        .arg(&["50", "false"])
+        .query(&mut con);
+    assert!(res.is_err(), "only a single fork child can run at a time");
+
+    redis::cmd("fork.kill").exec(&mut con)?;
+    let status: Option<String> = redis::cmd("fork.status").query(&mut con)?;
+    assert_eq!(status.as_deref(), Some("killed"));
+
+    Ok(())
+}
+
+#[test]
+fn test_rdb_save_and_load() -> Result<()> {
+    let mut con = start_server_w_module_get_connection("rdb")?;
+    let path = con.data_dir().join("backup.rdb");
+    let path = path.to_str().unwrap();
+
+    redis::cmd("SET").arg(&["x", "1"]).exec(&mut con)?;
+    redis::cmd("rdb.save").arg(path).exec(&mut con)?;
+
+    redis::cmd("SET").arg(&["x", "2"]).exec(&mut con)?;
+    redis::cmd("SET").arg(&["y", "3"]).exec(&mut con)?;
+    redis::cmd("rdb.load").arg(path).exec(&mut con)?;
+
+    let res: Option<String> = redis::cmd("GET").arg(&["x"]).query(&mut con)?;
+    assert_eq!(res.as_deref(), Some("1"));
+    let res: Option<String> = redis::cmd("GET").arg(&["y"]).query(&mut con)?;
+    assert_eq!(res, None);
+
+    let res: RedisResult<String> = redis::cmd("rdb.load")
+        .arg(&["/nonexistent/backup.rdb"])
+        .query(&mut con);
+    assert!(res.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_module_dict() -> Result<()> {
+    let mut con = start_server_w_module_get_connection("dict")?;
+
+    for key in ["b", "d", "a", "c", "e"] {
+        let res: Option<String> = redis::cmd("dict.set")
+            .arg(key)
+            .arg(key.to_uppercase())
+            .query(&mut con)?;
+        assert_eq!(res, None);
+    }
+    let res: Option<String> = redis::cmd("dict.set").arg(&["e", "E2"]).query(&mut con)?;
+    assert_eq!(res.as_deref(), Some("E"));
+
+    let res: Option<String> = redis::cmd("dict.get").arg(&["c"]).query(&mut con)?;
+    assert_eq!(res.as_deref(), Some("C"));
+    let res: Option<String> = redis::cmd("dict.del").arg(&["c"]).query(&mut con)?;
+    assert_eq!(res.as_deref(), Some("C"));
+    let res: Option<String> = redis::cmd("dict.get").arg(&["c"]).query(&mut con)?;
+    assert_eq!(res, None);
+
+    let res: Vec<String> = redis::cmd("dict.range").arg(&["b", "e"]).query(&mut con)?;
+    assert_eq!(res, ["b", "B", "d", "D"]);
+
+    let res: Vec<String> = redis::cmd("dict.seek_rev")
+        .arg(&["<=", "d"])
+        .query(&mut con)?;
+    assert_eq!(res, ["d", "D", "b", "B", "a", "A"]);
+
+    let res: RedisResult<Vec<String>> =
+        redis::cmd("dict.seek_rev").arg(&["~", "d"]).query(&mut con);
+    assert!(res.is_err());
+
+    let res: Vec<i64> = redis::cmd("dict.info").query(&mut con)?;
+    assert_eq!(res[0], 4);
+    assert!(res[1] > 0);
+
+    Ok(())
+}
+
 #[test]
 fn test_client_change_event() -> Result<()> {
     let mut con = start_server_w_module_get_connection("server_events")?;
@@ -802,6 +1331,90 @@ fn test_response() -> Result<()> {
     res.sort();
     assert_eq!(&res, &["b", "d"]);
 
+    let res: Vec<i64> = redis::cmd("stream.range")
+        .arg(&["1000"])
+        .query(&mut con)
+        .with_context(|| "failed to run stream.range")?;
+
+    assert_eq!(res, (0..1000).collect::<Vec<i64>>());
+
+    let res: Vec<i64> = redis::cmd("stream.range")
+        .arg(&["0"])
+        .query(&mut con)
+        .with_context(|| "failed to run stream.range")?;
+
+    assert!(res.is_empty());
+
+    let res: Value = redis::cmd("stream.map")
+        .arg(&["3"])
+        .query(&mut con)
+        .with_context(|| "failed to run stream.map")?;
+
+    // RESP2 connection, the map is sent as a flat array of keys and values
+    let res = res.as_sequence().unwrap();
+    assert_eq!(res.len(), 8);
+    assert_eq!(res[6], Value::BulkString(b"range".to_vec()));
+    assert_eq!(res[7].as_sequence().unwrap().len(), 3);
+
+    // RESP2 connection, the attributes are dropped and the push is sent as an array
+    let res: String = redis::cmd("reply.attributes")
+        .query(&mut con)
+        .with_context(|| "failed to run reply.attributes")?;
+
+    assert_eq!(res, "value");
+
+    let res: (String, i64) = redis::cmd("reply.push")
+        .query(&mut con)
+        .with_context(|| "failed to run reply.push")?;
+
+    assert_eq!(res, ("invalidate".to_owned(), 1));
+
+    let res: i64 = redis::cmd("reply.error")
+        .arg(&["one"])
+        .query(&mut con)
+        .with_context(|| "failed to run reply.error")?;
+
+    assert_eq!(res, 1);
+
+    let error = redis::cmd("reply.error")
+        .arg(&["two"])
+        .query::<i64>(&mut con)
+        .expect_err("reply.error should return an error");
+
+    assert_eq!(error.code(), Some("MYMOD_NOTFOUND"));
+    assert!(error.to_string().contains("no such item 'two'"));
+
+    let res: String = redis::cmd("info")
+        .arg(&["errorstats"])
+        .query(&mut con)
+        .with_context(|| "failed to run info errorstats")?;
+
+    assert!(res.contains("errorstat_MYMOD_NOTFOUND:count=1"));
+
+    // RESP2 connection, the struct is sent as a flat array of fields and values,
+    // in declaration order
+    let res: Vec<Value> = redis::cmd("reply.serde")
+        .arg(&["box", "a", "b"])
+        .query(&mut con)
+        .with_context(|| "failed to run reply.serde")?;
+
+    assert_eq!(
+        res,
+        vec![
+            Value::BulkString(b"name".to_vec()),
+            Value::BulkString(b"box".to_vec()),
+            Value::BulkString(b"qty".to_vec()),
+            Value::Int(3),
+            Value::BulkString(b"tags".to_vec()),
+            Value::Array(vec![
+                Value::BulkString(b"a".to_vec()),
+                Value::BulkString(b"b".to_vec()),
+            ]),
+            Value::BulkString(b"status".to_vec()),
+            Value::BulkString(b"Active".to_vec()),
+        ]
+    );
+
     Ok(())
 }
 
@@ -860,6 +1473,105 @@ fn test_valkey_value_derive() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_command_redacted_arguments() -> Result<()> {
+    let mut con = start_server_w_module_get_connection("proc_macro_commands")?;
+
+    redis::cmd("CONFIG")
+        .arg(&["SET", "slowlog-log-slower-than", "0"])
+        .exec(&mut con)?;
+    redis::cmd("SLOWLOG").arg(&["RESET"]).exec(&mut con)?;
+
+    let res: bool = redis::cmd("redacted_password")
+        .arg(&["alice", "secret"])
+        .query(&mut con)
+        .with_context(|| "failed to run redacted_password")?;
+    assert!(res);
+
+    let res: Vec<Vec<Value>> = redis::cmd("SLOWLOG").arg(&["GET", "1"]).query(&mut con)?;
+    let args: Vec<String> = redis::from_redis_value(&res[0][3])?;
+    assert_eq!(args, ["redacted_password", "alice", "(redacted)"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_async_command() -> Result<()> {
+    let server = start_server_w_module_get_connection("proc_macro_commands")?;
+    let port = server.port;
+    let (_guard, mut con) = server.into_parts();
+
+    redis::cmd("SET").arg(&["a", "abc"]).exec(&mut con)?;
+    redis::cmd("SET").arg(&["b", "de"]).exec(&mut con)?;
+    let res: i64 = redis::cmd("async_strlen_sum")
+        .arg(&["a", "b", "missing"])
+        .query(&mut con)
+        .with_context(|| "failed to run async_strlen_sum")?;
+    assert_eq!(res, 5);
+
+    redis::cmd("LPUSH").arg(&["list", "x"]).exec(&mut con)?;
+    let res: RedisResult<i64> = redis::cmd("async_strlen_sum")
+        .arg(&["a", "list"])
+        .query(&mut con);
+    assert_eq!(res.unwrap_err().code(), Some("WRONGTYPE"));
+
+    // A panicking command still replies, and the executor keeps running
+    let res: RedisResult<i64> = redis::cmd("async_panic").query(&mut con);
+    let error = res.expect_err("async_panic should reply with an error");
+    assert_eq!(error.to_string(), "ERR the async command panicked");
+    let res: i64 = redis::cmd("async_strlen_sum")
+        .arg(&["a"])
+        .query(&mut con)
+        .with_context(|| "failed to run async_strlen_sum")?;
+    assert_eq!(res, 3);
+
+    // A sleeping command does not hold up the others
+    let mut sleeping_con =
+        get_valkey_connection(port).with_context(|| FAILED_TO_CONNECT_TO_SERVER)?;
+    let handle = thread::spawn(move || -> RedisResult<i64> {
+        redis::cmd("async_sleep").arg(2000).query(&mut sleeping_con)
+    });
+    wait_for_blocked_clients(&mut con)?;
+    let start = Instant::now();
+    let res: i64 = redis::cmd("async_strlen_sum")
+        .arg(&["a", "b"])
+        .query(&mut con)
+        .with_context(|| "failed to run async_strlen_sum")?;
+    assert_eq!(res, 5);
+    assert!(start.elapsed() < Duration::from_millis(1000));
+    assert_eq!(handle.join().unwrap()?, 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_valkey_args_derive() -> Result<()> {
+    let mut con = start_server_w_module_get_connection("proc_macro_commands")?;
+
+    let res: Value = redis::cmd("valkey_args_derive")
+        .arg(&["key", "NX", "ex", "10", "aggregate", "MIN", "a", "b"])
+        .query(&mut con)
+        .with_context(|| "failed to run valkey_args_derive")?;
+
+    let res = res.as_sequence().unwrap();
+    assert_eq!(res.len(), 5);
+    assert_eq!(res[2], Value::Int(10));
+    assert_eq!(res[3], Value::SimpleString("min".to_owned()));
+    assert_eq!(res[4].as_sequence().unwrap().len(), 2);
+
+    let res: Result<Value, RedisError> = redis::cmd("valkey_args_derive")
+        .arg(&["key", "EX"])
+        .query(&mut con);
+    assert!(res.unwrap_err().to_string().contains("syntax error"));
+
+    let res: Result<Value, RedisError> = redis::cmd("valkey_args_derive")
+        .arg(&["key", "AGGREGATE", "avg"])
+        .query(&mut con);
+    assert!(res.unwrap_err().to_string().contains("syntax error"));
+
+    Ok(())
+}
+
 #[test]
 fn test_call_blocking() -> Result<()> {
     let mut con = start_server_w_module_get_connection("call")?;
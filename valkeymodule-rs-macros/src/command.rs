@@ -218,6 +218,8 @@ struct Args {
     tips: Option<String>,
     arity: i64,
     key_spec: Vec<KeySpecArg>,
+    #[serde(default)]
+    redact: Vec<i32>,
 }
 
 impl Parse for Args {
@@ -264,6 +266,10 @@ pub(crate) fn valkey_command(attr: TokenStream, item: TokenStream) -> TokenStrea
     let since_literal = to_token_stream(args.since);
     let tips_literal = to_token_stream(args.tips);
     let arity_literal = args.arity;
+    let redact = args.redact;
+    if redact.iter().any(|pos| *pos < 1) {
+        return quote! {compile_error!("Redacted argument positions must be greater than 0, the command name can not be redacted.");}.into();
+    }
     let key_spec_notes: Vec<_> = args
         .key_spec
         .iter()
@@ -329,6 +335,34 @@ pub(crate) fn valkey_command(attr: TokenStream, item: TokenStream) -> TokenStrea
         })
         .collect();
 
+    let run_command = if func.sig.asyncness.is_some() {
+        // The arguments are parsed on the main thread, the future runs on the executor
+        // with the client blocked until it completes. A panicking future still replies,
+        // so the client is unblocked.
+        quote! {
+            let args = match valkey_module::ValkeyArgs::from_args(args) {
+                Ok(args) => args,
+                Err(e) => return context.reply(Err(e)) as i32,
+            };
+            let thread_ctx = valkey_module::ThreadSafeContext::with_blocked_client(context.block_client());
+            valkey_module::executor::spawn(async move {
+                let response = valkey_module::executor::catch_unwind(#original_function_name(&thread_ctx, args))
+                    .await
+                    .unwrap_or(Err(valkey_module::ValkeyError::Str("ERR the async command panicked")));
+                thread_ctx.reply(response.map(|v| v.into()));
+            });
+            valkey_module::raw::REDISMODULE_OK as i32
+        }
+    } else {
+        quote! {
+            let response = match valkey_module::ValkeyArgs::from_args(args) {
+                Ok(args) => #original_function_name(&context, args),
+                Err(e) => Err(e),
+            };
+            context.reply(response.map(|v| v.into())) as i32
+        }
+    };
+
     let gen = quote! {
         #func
 
@@ -338,10 +372,14 @@ pub(crate) fn valkey_command(attr: TokenStream, item: TokenStream) -> TokenStrea
             argc: i32,
         ) -> i32 {
             let context = valkey_module::Context::new(ctx);
+            #(
+                if #redact < argc {
+                    let _ = context.redact_arg(#redact);
+                }
+            )*
 
             let args = valkey_module::decode_args(ctx, argv, argc);
-            let response = #original_function_name(&context, args);
-            context.reply(response.map(|v| v.into())) as i32
+            #run_command
         }
 
         #[linkme::distributed_slice(valkey_module::commands::COMMANDS_LIST)]
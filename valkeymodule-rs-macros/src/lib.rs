@@ -4,6 +4,7 @@ use syn::ItemFn;
 
 mod command;
 mod info_section;
+mod valkey_args;
 mod valkey_value;
 
 /// This proc macro allow to specify that the follow function is a Valkey command.
@@ -55,6 +56,9 @@ mod valkey_value;
 ///            which case it should be set to `keynumidx + 1`.)
 ///          * keystep - How many arguments should we skip after finding a
 ///            key, in order to find the next one?
+/// * redact (optional) - The positions of the arguments to redact from the slowlog and
+///   `MONITOR` output, such as passwords. The command name is at position 0. The arguments
+///   are redacted before the function runs, see `Context::redact_arg`.
 ///
 /// Example:
 /// The following example will register a command called `foo`.
@@ -79,6 +83,30 @@ mod valkey_value;
 /// }
 /// ```
 ///
+/// The second argument of the function is either the raw `Vec<ValkeyString>` or any type
+/// implementing `valkey_module::ValkeyArgs`, for example a struct using [`macro@ValkeyArgs`] derive.
+///
+/// The function can also be an `async fn`, taking a `&ThreadSafeContext<BlockedClient>`
+/// instead of the `&Context`. The client is then blocked while the future runs on the
+/// `valkey_module::executor`, without the GIL, which it takes with `ThreadSafeContext::lock_async`
+/// when needed, and is replied to once the future completes. Its arguments must be `Send`,
+/// such as `Vec<Vec<u8>>` or a derived struct of owned fields.
+///
+/// All the async commands of the module share the executor thread, which has no reactor.
+/// A future blocking it, e.g. with `ThreadSafeContext::lock` or blocking I/O, stalls the
+/// others, and futures needing the reactor of another runtime, such as tokio timers, panic
+/// or never complete. Use `valkey_module::executor::sleep` to wait.
+/// ```rust,no_run,ignore
+/// #[command({ flags: [ReadOnly], arity: -2, key_spec: [] })]
+/// async fn lengths(ctx: &ThreadSafeContext<BlockedClient>, args: Vec<Vec<u8>>) -> ValkeyResult {
+///     let mut lengths = Vec::new();
+///     for key in &args[1..] {
+///         lengths.push(ctx.lock_async().await.call("STRLEN", &[key.as_slice()])?);
+///     }
+///     Ok(ValkeyValue::Array(lengths))
+/// }
+/// ```
+///
 /// **Notice**, by default Valkey does not validate the command spec. User should validate the command keys on the module command code. The command spec is used for validation on cluster so Valkey can raise a cross slot error when needed.
 #[proc_macro_attribute]
 pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -517,6 +545,121 @@ pub fn valkey_value(item: TokenStream) -> TokenStream {
     valkey_value::valkey_value(item)
 }
 
+/// The reverse of [`ValkeyValue`] derive, implements [`valkey_module::FromValkeyValue`]
+/// and `TryFrom<ValkeyValue>` for a struct or an enum.
+///
+/// A struct is built out of a `ValkeyValue::Map`, a `ValkeyValue::OrderedMap`, a
+/// `ValkeyValue::MapEntries` or a flat `ValkeyValue::Array` of keys and values (the
+/// RESP2 representation of a map). Each field is taken from the entry named after it
+/// and converted with [`valkey_module::FromValkeyValue`]. Entries that don't match any field are ignored.
+/// A missing [Option] field is set to `None`, any other missing field is an error
+/// unless it has the `default` attribute.
+///
+/// Fields support the same `ValkeyValueAttr` attribute as the [`ValkeyValue`] derive:
+///
+/// * flatten - the field is built out of the parent entries not used by the other fields.
+/// * default - a missing field is set to its [Default] value.
+///
+/// Enum variants must either have no fields, in which case they are matched by
+/// name against a string value, or hold a single field. Variants holding a field
+/// are tried in declaration order and the first one whose field can be built out
+/// of the value is returned.
+///
+/// Example:
+///
+/// ```rust,no_run,ignore
+/// #[derive(ValkeyValue, FromValkeyValue)]
+/// struct Limits {
+///     max_memory: i64,
+/// }
+///
+/// #[derive(ValkeyValue, FromValkeyValue)]
+/// struct Config {
+///     name: String,
+///     #[ValkeyValueAttr{default: true}]
+///     retries: i64,
+///     #[ValkeyValueAttr{flatten: true}]
+///     limits: Limits,
+/// }
+///
+/// let config = Config::try_from(ctx.call("HGETALL", &["config"])?)?;
+/// ```
+#[proc_macro_derive(FromValkeyValue, attributes(ValkeyValueAttr))]
+pub fn from_valkey_value(item: TokenStream) -> TokenStream {
+    valkey_value::from_valkey_value(item)
+}
+
+/// Implements [`valkey_module::ValkeyArgs`] for a struct, so the command arguments
+/// can be parsed declaratively instead of walking them with `NextArg`. The first
+/// argument (the command name) is skipped.
+///
+/// Fields are handled according to the `ValkeyArgsAttr` field attribute:
+///
+/// * no attribute - a positional argument. Positional arguments are parsed first, in
+///   declaration order. A missing positional argument results in an arity error.
+/// * keyword - an optional `KEYWORD value` pair, the field must be an [Option].
+/// * flag - an optional token such as `NX` or `XX`, the field must be a [bool].
+/// * variadic - a [Vec] collecting all the remaining arguments. At most one such field
+///   is allowed.
+///
+/// Keywords and flags are matched case-insensitively and may appear in any order after
+/// the positional arguments. An unknown token is collected by the variadic field if there
+/// is one, otherwise the parsing fails with `ERR syntax error`. Extra arguments on a struct
+/// with only positional fields result in an arity error.
+///
+/// Each value is parsed with [`valkey_module::FromValkeyArg`]. The derive can also be set
+/// on a fieldless Enum to implement [`valkey_module::FromValkeyArg`], in which case each
+/// variant is matched case-insensitively against its name in upper case, or against the
+/// name given with the `name` attribute.
+///
+/// A command defined with [`macro@command`] can take the struct directly instead of
+/// `Vec<ValkeyString>`, a parsing error is then replied without calling the handler.
+///
+/// Example:
+///
+/// ```rust,no_run,ignore
+/// #[derive(ValkeyArgs)]
+/// enum Aggregate {
+///     Sum,
+///     Min,
+///     Max,
+/// }
+///
+/// #[derive(ValkeyArgs)]
+/// struct AddArgs {
+///     key: ValkeyString,
+///     #[ValkeyArgsAttr{flag: "NX"}]
+///     nx: bool,
+///     #[ValkeyArgsAttr{keyword: "EX"}]
+///     ex: Option<u64>,
+///     #[ValkeyArgsAttr{keyword: "AGGREGATE"}]
+///     aggregate: Option<Aggregate>,
+///     #[ValkeyArgsAttr{variadic: true}]
+///     members: Vec<String>,
+/// }
+///
+/// #[command(
+///     {
+///         flags: [Write],
+///         arity: -2,
+///         key_spec: [
+///             {
+///                 flags: [ReadWrite, Insert],
+///                 begin_search: Index({ index : 1 }),
+///                 find_keys: Range({ last_key : 0, steps : 1, limit : 0 }),
+///             }
+///         ]
+///     }
+/// )]
+/// fn add(_ctx: &Context, args: AddArgs) -> ValkeyResult {
+///     Ok((args.members.len() as i64).into())
+/// }
+/// ```
+#[proc_macro_derive(ValkeyArgs, attributes(ValkeyArgsAttr))]
+pub fn valkey_args(item: TokenStream) -> TokenStream {
+    valkey_args::valkey_args(item)
+}
+
 /// A procedural macro which registers this function as the custom
 /// `INFO` command handler. There might be more than one handler, each
 /// adding new information to the context.
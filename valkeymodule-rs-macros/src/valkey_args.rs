@@ -0,0 +1,228 @@
+use proc_macro::TokenStream;
+use proc_macro2::Ident;
+use quote::quote;
+use serde::Deserialize;
+use serde_syn::{config, from_stream};
+use syn::{
+    parse,
+    parse::{Parse, ParseStream},
+    parse_macro_input, Attribute, Data, DataEnum, DataStruct, DeriveInput, Fields,
+};
+
+/// Represent the attributes of a single field or enum variant.
+#[derive(Debug, Deserialize, Default)]
+struct ArgAttr {
+    keyword: Option<String>,
+    flag: Option<String>,
+    #[serde(default)]
+    variadic: bool,
+    name: Option<String>,
+}
+
+impl Parse for ArgAttr {
+    fn parse(input: ParseStream) -> parse::Result<Self> {
+        from_stream(config::JSONY, input)
+    }
+}
+
+/// Extract the `ValkeyArgsAttr` attribute out of the given attributes list,
+/// ignoring any other attribute (such as doc comments).
+fn arg_attr(attrs: Vec<Attribute>) -> Result<ArgAttr, String> {
+    let mut attrs = attrs
+        .into_iter()
+        .filter(|attr| attr.path.is_ident("ValkeyArgsAttr"));
+    let attr = match attrs.next() {
+        Some(attr) => attr,
+        None => return Ok(ArgAttr::default()),
+    };
+    if attrs.next().is_some() {
+        return Err("Expected at most a single ValkeyArgsAttr attribute for each field".to_owned());
+    }
+    parse_macro_input::parse(attr.tokens.into()).map_err(|e| format!("{e}"))
+}
+
+/// Generate [FromValkeyArg] implementation for a fieldless Enum.
+/// Each variant is matched, case-insensitively, against its name in
+/// upper case or against the name given with the `name` attribute.
+fn enum_valkey_args(enum_name: Ident, enum_data: DataEnum) -> TokenStream {
+    let variants = enum_data
+        .variants
+        .into_iter()
+        .map(|v| {
+            if !matches!(v.fields, Fields::Unit) {
+                return Err(
+                    "ValkeyArgs derive can only be apply on enum without fields.".to_owned(),
+                );
+            }
+            let attr = arg_attr(v.attrs)?;
+            let name = attr
+                .name
+                .unwrap_or_else(|| v.ident.to_string().to_uppercase());
+            Ok((v.ident, name))
+        })
+        .collect::<Result<Vec<_>, String>>();
+
+    let (variants, names): (Vec<_>, Vec<_>) = match variants {
+        Ok(v) => v.into_iter().unzip(),
+        Err(e) => return quote! {compile_error!(#e);}.into(),
+    };
+
+    let res = quote! {
+        impl valkey_module::FromValkeyArg for #enum_name {
+            fn from_valkey_arg(arg: valkey_module::ValkeyString) -> valkey_module::ValkeyResult<Self> {
+                #(
+                    if arg.as_slice().eq_ignore_ascii_case(#names.as_bytes()) {
+                        return Ok(#enum_name::#variants);
+                    }
+                )*
+                Err(valkey_module::ValkeyError::Str("ERR syntax error"))
+            }
+        }
+    };
+    res.into()
+}
+
+/// Generate [ValkeyArgs] implementation for a struct.
+/// Fields without attributes are positional arguments and are parsed first,
+/// in declaration order. They are followed by the `keyword` and `flag` fields,
+/// in any order. A `variadic` field collects all the remaining arguments.
+fn struct_valkey_args(struct_name: Ident, struct_data: DataStruct) -> TokenStream {
+    let fields = match struct_data.fields {
+        Fields::Named(f) => f,
+        _ => {
+            return quote! {compile_error!("ValkeyArgs derive can only be apply on struct with named fields.");}.into()
+        }
+    };
+
+    let fields = fields
+        .named
+        .into_iter()
+        .map(|v| {
+            let name = v
+                .ident
+                .ok_or("Field without a name is not supported.".to_owned())?;
+            let attr = arg_attr(v.attrs)?;
+            let kinds = [attr.keyword.is_some(), attr.flag.is_some(), attr.variadic];
+            if kinds.iter().filter(|v| **v).count() > 1 {
+                return Err(format!(
+                    "Field '{name}' can only be one of keyword, flag or variadic."
+                ));
+            }
+            Ok((name, attr))
+        })
+        .collect::<Result<Vec<_>, String>>();
+
+    let fields = match fields {
+        Ok(f) => f,
+        Err(e) => return quote! {compile_error!(#e);}.into(),
+    };
+
+    let all_fields: Vec<_> = fields.iter().map(|(name, _)| name.clone()).collect();
+    let mut positional = Vec::new();
+    let mut keywords = Vec::new();
+    let mut keyword_names = Vec::new();
+    let mut flags = Vec::new();
+    let mut flag_names = Vec::new();
+    let mut variadic = Vec::new();
+    for (name, attr) in fields {
+        if let Some(keyword) = attr.keyword {
+            keywords.push(name);
+            keyword_names.push(keyword);
+        } else if let Some(flag) = attr.flag {
+            flags.push(name);
+            flag_names.push(flag);
+        } else if attr.variadic {
+            variadic.push(name);
+        } else {
+            positional.push(name);
+        }
+    }
+
+    if variadic.len() > 1 {
+        return quote! {compile_error!("Only a single variadic field is allowed.");}.into();
+    }
+
+    let rest = if !keywords.is_empty() || !flags.is_empty() {
+        let unmatched = match variadic.first() {
+            Some(variadic) => quote! {
+                #variadic.push(valkey_module::FromValkeyArg::from_valkey_arg(__valkey_arg)?);
+                for __valkey_arg in __valkey_args.by_ref() {
+                    #variadic.push(valkey_module::FromValkeyArg::from_valkey_arg(__valkey_arg)?);
+                }
+                break;
+            },
+            None => quote! {
+                return Err(valkey_module::ValkeyError::Str("ERR syntax error"));
+            },
+        };
+        quote! {
+            while let Some(__valkey_arg) = __valkey_args.next() {
+                #(
+                    if __valkey_arg.as_slice().eq_ignore_ascii_case(#keyword_names.as_bytes()) {
+                        let __valkey_value = __valkey_args
+                            .next()
+                            .ok_or(valkey_module::ValkeyError::Str("ERR syntax error"))?;
+                        #keywords = Some(valkey_module::FromValkeyArg::from_valkey_arg(__valkey_value)?);
+                        continue;
+                    }
+                )*
+                #(
+                    if __valkey_arg.as_slice().eq_ignore_ascii_case(#flag_names.as_bytes()) {
+                        #flags = true;
+                        continue;
+                    }
+                )*
+                #unmatched
+            }
+        }
+    } else if let Some(variadic) = variadic.first() {
+        quote! {
+            for __valkey_arg in __valkey_args {
+                #variadic.push(valkey_module::FromValkeyArg::from_valkey_arg(__valkey_arg)?);
+            }
+        }
+    } else {
+        quote! {
+            if __valkey_args.next().is_some() {
+                return Err(valkey_module::ValkeyError::WrongArity);
+            }
+        }
+    };
+
+    let res = quote! {
+        impl valkey_module::ValkeyArgs for #struct_name {
+            fn from_args(__valkey_args: Vec<valkey_module::ValkeyString>) -> valkey_module::ValkeyResult<Self> {
+                #[allow(unused_mut)]
+                let mut __valkey_args = __valkey_args.into_iter().skip(1);
+                #(
+                    let #positional = valkey_module::FromValkeyArg::from_valkey_arg(
+                        __valkey_args.next().ok_or(valkey_module::ValkeyError::WrongArity)?,
+                    )?;
+                )*
+                #(let mut #keywords = None;)*
+                #(let mut #flags = false;)*
+                #(let mut #variadic = Vec::new();)*
+                #rest
+                Ok(#struct_name {
+                    #(#all_fields,)*
+                })
+            }
+        }
+    };
+    res.into()
+}
+
+/// Implementation for [ValkeyArgs] derive proc macro.
+/// Runs the relevant code generation base on the element
+/// the proc macro was used on. Currently supports fieldless
+/// Enums and structs with named fields.
+pub fn valkey_args(item: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(item);
+    let name = input.ident;
+    match input.data {
+        Data::Struct(s) => struct_valkey_args(name, s),
+        Data::Enum(e) => enum_valkey_args(name, e),
+        _ => quote! {compile_error!("ValkeyArgs derive can only be apply on struct or enum.");}
+            .into(),
+    }
+}
@@ -6,7 +6,7 @@ use serde_syn::{config, from_stream};
 use syn::{
     parse,
     parse::{Parse, ParseStream},
-    parse_macro_input, Data, DataEnum, DataStruct, DeriveInput, Fields,
+    parse_macro_input, Attribute, Data, DataEnum, DataStruct, DeriveInput, Fields,
 };
 
 /// Generate [From] implementation for [ValkeyValue] for Enum.
@@ -36,7 +36,10 @@ fn enum_valkey_value(struct_name: Ident, enum_data: DataEnum) -> TokenStream {
 /// Represent a single field attributes
 #[derive(Debug, Deserialize, Default)]
 struct FieldAttr {
+    #[serde(default)]
     flatten: bool,
+    #[serde(default)]
+    default: bool,
 }
 
 impl Parse for FieldAttr {
@@ -144,3 +147,190 @@ pub fn valkey_value(item: TokenStream) -> TokenStream {
         _ => quote! {compile_error!("ValkeyValue derive can only be apply on struct.")}.into(),
     }
 }
+
+/// Extract the `ValkeyValueAttr` attribute out of the given attributes list,
+/// ignoring any other attribute (such as doc comments).
+fn field_attr(attrs: Vec<Attribute>) -> Result<FieldAttr, String> {
+    let mut attrs = attrs
+        .into_iter()
+        .filter(|attr| attr.path.is_ident("ValkeyValueAttr"));
+    let attr = match attrs.next() {
+        Some(attr) => attr,
+        None => return Ok(FieldAttr::default()),
+    };
+    if attrs.next().is_some() {
+        return Err("Expected at most a single ValkeyValueAttr attribute for each field".to_owned());
+    }
+    parse_macro_input::parse(attr.tokens.into()).map_err(|e| format!("{e}"))
+}
+
+/// Generate [FromValkeyValue] implementation for an Enum.
+/// Unit variants are matched against their name, other variants must hold
+/// a single field and are tried in declaration order, the first variant
+/// whose field can be built out of the value is returned. The variants are
+/// tried against the borrowed value, so it is not cloned for each of them.
+fn enum_from_valkey_value(enum_name: Ident, enum_data: DataEnum) -> TokenStream {
+    let mut units = Vec::new();
+    let mut newtypes = Vec::new();
+    for v in enum_data.variants {
+        match v.fields {
+            Fields::Unit => units.push(v.ident),
+            Fields::Unnamed(f) if f.unnamed.len() == 1 => newtypes.push(v.ident),
+            _ => {
+                return quote! {compile_error!("FromValkeyValue derive can only be apply on enum variants without fields or with a single unnamed field.");}.into()
+            }
+        }
+    }
+    let unit_names: Vec<_> = units.iter().map(|v| v.to_string()).collect();
+    let error = format!("ERR value can not be converted to {enum_name}");
+
+    let res = quote! {
+        impl valkey_module::FromValkeyValue for #enum_name {
+            fn from_valkey_value(value: valkey_module::redisvalue::ValkeyValue) -> valkey_module::ValkeyResult<Self> {
+                Self::from_valkey_value_ref(&value)
+            }
+
+            fn from_valkey_value_ref(value: &valkey_module::redisvalue::ValkeyValue) -> valkey_module::ValkeyResult<Self> {
+                #(
+                    if valkey_module::redisvalue::as_str(value) == Some(#unit_names) {
+                        return Ok(#enum_name::#units);
+                    }
+                )*
+                #(
+                    if let Ok(v) = valkey_module::FromValkeyValue::from_valkey_value_ref(value) {
+                        return Ok(#enum_name::#newtypes(v));
+                    }
+                )*
+                Err(valkey_module::ValkeyError::Str(#error))
+            }
+        }
+
+        impl TryFrom<valkey_module::redisvalue::ValkeyValue> for #enum_name {
+            type Error = valkey_module::ValkeyError;
+            fn try_from(value: valkey_module::redisvalue::ValkeyValue) -> Result<Self, Self::Error> {
+                valkey_module::FromValkeyValue::from_valkey_value(value)
+            }
+        }
+    };
+    res.into()
+}
+
+/// Generate [FromValkeyValue] implementation for a struct.
+/// The struct is built out of a [ValkeyValue::Map], [ValkeyValue::OrderedMap],
+/// [ValkeyValue::MapEntries] or a flat [ValkeyValue::Array] of keys and values. Each field is taken from
+/// the entry with the field name, a missing field is set to its default value
+/// if it has the `default` attribute, `None` for [Option] fields. A `flatten`
+/// field is built out of all the entries not used by the other fields.
+fn struct_from_valkey_value(struct_name: Ident, struct_data: DataStruct) -> TokenStream {
+    let fields = match struct_data.fields {
+        Fields::Named(f) => f,
+        _ => {
+            return quote! {compile_error!("FromValkeyValue derive can only be apply on struct with named fields.");}.into()
+        }
+    };
+
+    let fields = fields
+        .named
+        .into_iter()
+        .map(|v| {
+            let name = v
+                .ident
+                .ok_or("Field without a name is not supported.".to_owned())?;
+            Ok((name, field_attr(v.attrs)?))
+        })
+        .collect::<Result<Vec<_>, String>>();
+
+    let fields = match fields {
+        Ok(f) => f,
+        Err(e) => return quote! {compile_error!(#e);}.into(),
+    };
+
+    let all_fields: Vec<_> = fields.iter().map(|(name, _)| name.clone()).collect();
+    let mut entry_fields = Vec::new();
+    let mut missing = Vec::new();
+    let mut flatten_fields = Vec::new();
+    for (name, attr) in fields {
+        if attr.flatten {
+            flatten_fields.push(name);
+            continue;
+        }
+        let error = format!("ERR missing field '{name}'");
+        missing.push(if attr.default {
+            quote! { Default::default() }
+        } else {
+            quote! {
+                valkey_module::FromValkeyValue::from_valkey_value(valkey_module::redisvalue::ValkeyValue::Null)
+                    .map_err(|_| valkey_module::ValkeyError::Str(#error))?
+            }
+        });
+        entry_fields.push(name);
+    }
+    let entry_names: Vec<_> = entry_fields.iter().map(|v| v.to_string()).collect();
+
+    let res = quote! {
+        impl valkey_module::FromValkeyValue for #struct_name {
+            fn from_valkey_value(value: valkey_module::redisvalue::ValkeyValue) -> valkey_module::ValkeyResult<Self> {
+                #[allow(unused_mut)]
+                let mut __entries = valkey_module::redisvalue::map_entries(value)?;
+                #(
+                    let #entry_fields = match valkey_module::redisvalue::take_entry(&mut __entries, #entry_names) {
+                        Some(v) => valkey_module::FromValkeyValue::from_valkey_value(v)?,
+                        None => #missing,
+                    };
+                )*
+                #(
+                    let #flatten_fields = valkey_module::FromValkeyValue::from_valkey_value(
+                        valkey_module::redisvalue::ValkeyValue::OrderedMap(__entries.iter().cloned().collect()),
+                    )?;
+                )*
+                Ok(#struct_name {
+                    #(#all_fields,)*
+                })
+            }
+
+            fn from_valkey_value_ref(value: &valkey_module::redisvalue::ValkeyValue) -> valkey_module::ValkeyResult<Self> {
+                #[allow(unused_mut)]
+                let mut __entries = valkey_module::redisvalue::map_entries_ref(value)?;
+                #(
+                    let #entry_fields = match valkey_module::redisvalue::take_entry(&mut __entries, #entry_names) {
+                        Some(v) => valkey_module::FromValkeyValue::from_valkey_value_ref(v)?,
+                        None => #missing,
+                    };
+                )*
+                #(
+                    let #flatten_fields = valkey_module::FromValkeyValue::from_valkey_value(
+                        valkey_module::redisvalue::ValkeyValue::OrderedMap(
+                            __entries.iter().map(|(k, v)| (k.clone(), (*v).clone())).collect(),
+                        ),
+                    )?;
+                )*
+                Ok(#struct_name {
+                    #(#all_fields,)*
+                })
+            }
+        }
+
+        impl TryFrom<valkey_module::redisvalue::ValkeyValue> for #struct_name {
+            type Error = valkey_module::ValkeyError;
+            fn try_from(value: valkey_module::redisvalue::ValkeyValue) -> Result<Self, Self::Error> {
+                valkey_module::FromValkeyValue::from_valkey_value(value)
+            }
+        }
+    };
+    res.into()
+}
+
+/// Implementation for [FromValkeyValue] derive proc macro.
+/// Runs the relevant code generation base on the element
+/// the proc macro was used on. Currently supports Enums and
+/// structs.
+pub fn from_valkey_value(item: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(item);
+    let name = input.ident;
+    match input.data {
+        Data::Struct(s) => struct_from_valkey_value(name, s),
+        Data::Enum(e) => enum_from_valkey_value(name, e),
+        _ => quote! {compile_error!("FromValkeyValue derive can only be apply on struct or enum.");}
+            .into(),
+    }
+}